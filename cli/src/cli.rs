@@ -1,17 +1,52 @@
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+use pokefight_core::{
+    Arena, BattleFormat, DraftPool, PokemonChoice, RosterEntry, Ruleset, Terrain, Weather,
+};
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 pub struct Cli {
+    /// Draw Pokemon from the bundled Pokedex instead of PokeAPI
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    /// Never contact PokeAPI; only use Pokemon already in the local cache
+    #[arg(long, global = true, conflicts_with_all = ["offline", "no_cache"])]
+    pub cache_only: bool,
+
+    /// Bypass the local PokeAPI response cache
+    #[arg(long, global = true)]
+    pub no_cache: bool,
+
+    /// Seconds to wait for a PokeAPI response before retrying
+    #[arg(
+        long,
+        global = true,
+        default_value_t = 10,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub timeout: u64,
+
     #[command(subcommand)]
     pub commands: Option<Commands>,
 }
 
+// Parsed once per run, so the size of the tournament options does not matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 pub enum Commands {
+    /// Run a tournament to decide who does the chore
     Tournament {
-        #[arg(short, long, num_args = 0..)]
-        names: Vec<String>,
+        /// Trainer names, optionally with a Pokemon: `Ash=pikachu` or `Misty:121`
+        #[arg(short, long, num_args = 0.., value_parser = parse_participant)]
+        names: Vec<RosterEntry>,
+
+        /// Trainers from a roster file (.json or .toml), entered before `--names`.
+        /// A trainer's item is held by the lead only
+        #[arg(long)]
+        roster: Option<PathBuf>,
 
         #[arg(short, long)]
         title: String,
@@ -21,7 +56,150 @@ pub enum Commands {
 
         #[arg(short, long, default_value = "text")]
         format: OutputFormat,
+
+        /// Maximum number of Pokemon fetched at the same time
+        #[arg(long, default_value_t = 8)]
+        concurrency: usize,
+
+        #[command(flatten)]
+        pool: PoolArgs,
+
+        /// Custom type effectiveness chart (.json or .toml)
+        #[arg(long)]
+        type_chart: Option<PathBuf>,
+
+        /// Damage formula: `main-series` or `classic`
+        #[arg(long, default_value = "main-series")]
+        ruleset: Ruleset,
+
+        /// Seed for drafting and battles; reuse it to replay a tournament
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Level every Pokemon fights at
+        #[arg(long, default_value_t = 50, value_parser = clap::value_parser!(u8).range(1..=100))]
+        level: u8,
+
+        /// Give every Pokemon random IVs and a random nature
+        #[arg(long)]
+        random_builds: bool,
+
+        /// Pokemon per trainer: 1 for singles, up to 6. Picked Pokemon lead
+        /// their team, the rest is drawn at random
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=6))]
+        team_size: u8,
+
+        /// Battle strategy of trainers without one in the roster: `random`,
+        /// `greedy`, `type-aware` or `lookahead`, e.g. `lookahead-3` (depth 1 to 4)
+        #[arg(long)]
+        strategy: Option<String>,
+
+        /// Pokemon each trainer has in battle at once: `singles` or `doubles`
+        #[arg(long, default_value = "singles")]
+        battle_format: BattleFormat,
+
+        #[command(flatten)]
+        arena: ArenaArgs,
+    },
+    /// Replay the battles of a tournament saved with `--format json`
+    Replay {
+        /// Tournament or single match JSON with a battle event log
+        file: PathBuf,
+
+        /// Playback speed from 0.1 to 100, e.g. `2` for twice as fast
+        #[arg(long, default_value_t = 1.0, value_parser = parse_speed)]
+        speed: f64,
+
+        /// Wait for Enter before every event instead of playing on
+        #[arg(long)]
+        step: bool,
+
+        /// Only replay this match of the tournament, counting from 1
+        #[arg(long = "match")]
+        match_number: Option<usize>,
+    },
+    /// Inspect and manage the local PokeAPI response cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheCommand,
+    },
+}
+
+/// Restrictions on which Pokemon can be drawn
+#[derive(Args)]
+pub struct PoolArgs {
+    /// Only draw from these generations, e.g. `--generations 1,2`
+    #[arg(long, value_delimiter = ',')]
+    pub generations: Vec<u8>,
+
+    /// Only draw Pokemon having one of these types, e.g. `--types fire,water`
+    #[arg(long, value_delimiter = ',')]
+    pub types: Vec<String>,
+
+    /// Exclude legendary and mythical Pokemon
+    #[arg(long)]
+    pub no_legendaries: bool,
+
+    /// Maximum base stat total of a drawn Pokemon
+    #[arg(long)]
+    pub max_bst: Option<i64>,
+
+    /// Never give two trainers the same species
+    #[arg(long)]
+    pub unique: bool,
+}
+
+impl PoolArgs {
+    pub fn to_draft_pool(&self) -> DraftPool {
+        DraftPool {
+            generations: self.generations.clone(),
+            types: self.types.clone(),
+            exclude_legendaries: self.no_legendaries,
+            max_base_stat_total: self.max_bst,
+            unique_species: self.unique,
+        }
+    }
+}
+
+/// Weather and terrain every battle is fought in
+#[derive(Args)]
+pub struct ArenaArgs {
+    /// Weather: `sun`, `rain`, `sandstorm`, `hail` or `snow`
+    #[arg(long)]
+    pub weather: Option<Weather>,
+
+    /// Terrain: `electric`, `grassy`, `psychic` or `misty`
+    #[arg(long)]
+    pub terrain: Option<Terrain>,
+
+    /// Rounds weather and terrain last; the whole battle by default
+    #[arg(long)]
+    pub field_turns: Option<u32>,
+}
+
+impl ArenaArgs {
+    pub fn to_arena(&self) -> Arena {
+        Arena::default()
+            .with_weather(self.weather)
+            .with_terrain(self.terrain)
+            .with_turns(self.field_turns)
+    }
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommand {
+    /// Fetch a range of Pokedex numbers into the cache
+    Warm {
+        #[arg(long, default_value_t = 1)]
+        from: u32,
+
+        #[arg(long, default_value_t = 151)]
+        to: u32,
     },
+    /// Show where the cache lives and how much it holds
+    Info,
+    /// Delete every cached entry
+    Clear,
 }
 
 #[derive(Clone, clap::ValueEnum)]
@@ -29,3 +207,39 @@ pub enum OutputFormat {
     Text,
     Json,
 }
+
+fn parse_participant(s: &str) -> Result<RosterEntry, String> {
+    let (name, pokemon) = match s.split_once(['=', ':']) {
+        Some((name, pokemon)) => {
+            let pokemon = pokemon
+                .parse()
+                .map_err(|_| format!("missing Pokemon after the trainer name in '{}'", s))?;
+            (name, pokemon)
+        }
+        None => (s, PokemonChoice::Random),
+    };
+    if name.trim().is_empty() {
+        return Err(format!("missing trainer name in '{}'", s));
+    }
+    Ok(RosterEntry {
+        name: name.trim().to_string(),
+        pokemon,
+        item: None,
+        strategy: None,
+    })
+}
+
+/// Slowest and fastest replay speeds, so event delays stay within reason.
+const REPLAY_SPEEDS: std::ops::RangeInclusive<f64> = 0.1..=100.0;
+
+fn parse_speed(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(speed) if REPLAY_SPEEDS.contains(&speed) => Ok(speed),
+        _ => Err(format!(
+            "'{}' is not a speed from {} to {}",
+            s,
+            REPLAY_SPEEDS.start(),
+            REPLAY_SPEEDS.end()
+        )),
+    }
+}
@@ -1,10 +1,13 @@
-use pokefight_core::{MatchResult, TournamentResult};
-use std::collections::HashMap;
+use pokefight_core::{
+    BattleEvent, BattleFormat, Fighter, MatchResult, StatusCondition, TournamentResult,
+};
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 
 // ANSI color codes
 const GREEN: &str = "\x1b[32m";
 const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
 const RESET: &str = "\x1b[0m";
 const BOLD: &str = "\x1b[1m";
 
@@ -37,7 +40,7 @@ impl<'a> fmt::Display for TournamentDisplay<'a> {
         for match_result in &self.result.matches {
             rounds
                 .entry(match_result.round)
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(match_result);
             max_round = max_round.max(match_result.round);
         }
@@ -88,6 +91,9 @@ impl<'a> fmt::Display for TournamentDisplay<'a> {
                             match_result.fighter2_pokemon,
                             RESET
                         ));
+                        if let Some(highlights) = get_highlights(match_result) {
+                            round_lines.push(highlights);
+                        }
                         round_lines.push(String::new());
                     }
                 }
@@ -151,12 +157,38 @@ impl<'a> fmt::Display for TournamentDisplay<'a> {
             "{}{}🏆 CHAMPION: {} 🏆{}{}",
             BOLD, GREEN, self.result.champion, RESET, RESET
         )?;
+        writeln!(f, "Seed: {}", self.result.seed)?;
+        if self.result.format != BattleFormat::Singles {
+            writeln!(f, "Format: {}", self.result.format)?;
+        }
+        if !self.result.arena.is_neutral() {
+            writeln!(f, "Arena: {}", self.result.arena)?;
+        }
         writeln!(f, "{}\n", separator)?;
 
         Ok(())
     }
 }
 
+fn get_highlights(match_result: &MatchResult) -> Option<String> {
+    let mut highlights = Vec::new();
+    match match_result.critical_hits {
+        0 => {}
+        1 => highlights.push("1 critical hit".to_string()),
+        n => highlights.push(format!("{} critical hits", n)),
+    }
+    match match_result.misses {
+        0 => {}
+        1 => highlights.push("1 miss".to_string()),
+        n => highlights.push(format!("{} misses", n)),
+    }
+    if highlights.is_empty() {
+        None
+    } else {
+        Some(format!("  * {}", highlights.join(", ")))
+    }
+}
+
 fn get_round_name(round_idx: usize, total_rounds: usize) -> String {
     match total_rounds - round_idx {
         1 => "FINALS".to_string(),
@@ -165,3 +197,261 @@ fn get_round_name(round_idx: usize, total_rounds: usize) -> String {
         _ => format!("ROUND {}", round_idx + 1),
     }
 }
+
+/// Width of the HP bars in the replay, in characters.
+const HP_BAR_WIDTH: usize = 20;
+
+/// Messages shown below the HP bars while replaying a battle.
+const REPLAY_MESSAGES: usize = 8;
+
+/// A Pokemon in battle during a replay.
+struct Combatant {
+    name: String,
+    hp: i64,
+    max_hp: i64,
+    status: Option<StatusCondition>,
+}
+
+/// Plays back the event log of a match one event at a time. Every event
+/// updates the Pokemon in battle and adds messages, and displaying the
+/// replay draws the HP bars above the latest messages.
+pub struct BattleReplay<'a> {
+    result: &'a MatchResult,
+    /// Pokemon in battle by (side, slot).
+    combatants: BTreeMap<(usize, usize), Combatant>,
+    messages: Vec<String>,
+}
+
+impl<'a> BattleReplay<'a> {
+    pub fn new(result: &'a MatchResult) -> Self {
+        Self {
+            result,
+            combatants: BTreeMap::new(),
+            messages: Vec::new(),
+        }
+    }
+
+    /// Applies `event`, returning whether it added any message worth a pause.
+    pub fn apply(&mut self, event: &BattleEvent) -> bool {
+        let before = self.messages.len();
+        match event {
+            BattleEvent::TurnStart { turn } => {
+                self.messages
+                    .push(format!("{}-- Turn {} --{}", BOLD, turn, RESET));
+            }
+            BattleEvent::SwitchIn {
+                fighter,
+                hp,
+                max_hp,
+            } => {
+                self.combatants.insert(
+                    (fighter.side, fighter.slot),
+                    Combatant {
+                        name: fighter.name.clone(),
+                        hp: *hp,
+                        max_hp: *max_hp,
+                        status: None,
+                    },
+                );
+                let message = format!("{} sent out {}!", self.trainer(fighter.side), fighter.name);
+                self.messages.push(message);
+            }
+            BattleEvent::MoveUsed {
+                fighter,
+                move_name,
+                move_type,
+            } => {
+                let message = format!("{} used {} ({})!", self.name(fighter), move_name, move_type);
+                self.messages.push(message);
+            }
+            BattleEvent::Miss { target, .. } => {
+                let message = format!("The attack missed {}!", self.name(target));
+                self.messages.push(message);
+            }
+            BattleEvent::CriticalHit { .. } => {
+                self.messages
+                    .push(format!("{}{}A critical hit!{}", BOLD, YELLOW, RESET));
+            }
+            BattleEvent::Damage {
+                target,
+                damage,
+                hp,
+                effectiveness,
+            } => {
+                let name = self.name(target);
+                if *effectiveness == 0.0 {
+                    self.messages.push(format!("It doesn't affect {}...", name));
+                } else if *effectiveness > 1.0 {
+                    self.messages
+                        .push(format!("{}It's super effective!{}", GREEN, RESET));
+                } else if *effectiveness < 1.0 {
+                    self.messages
+                        .push(format!("{}It's not very effective...{}", RED, RESET));
+                }
+                // Damage beyond the HP that was left does not show
+                let previous = self.combatant(target).map_or(*damage, |c| c.hp);
+                let lost = (*damage).min(previous - hp);
+                if lost > 0 {
+                    self.messages.push(format!("{} lost {} HP.", name, lost));
+                }
+                self.set_hp(target, *hp);
+            }
+            BattleEvent::Immobilized { fighter, status } => {
+                let message = format!("{} can't move because of {}!", self.name(fighter), status);
+                self.messages.push(message);
+            }
+            BattleEvent::StatusApplied { target, status } => {
+                let message = format!("{} is afflicted by {}!", self.name(target), status);
+                self.messages.push(message);
+                if let Some(combatant) = self.combatant(target) {
+                    combatant.status = Some(*status);
+                }
+            }
+            BattleEvent::StatusCured { fighter } => {
+                let name = self.name(fighter);
+                if let Some(status) = self.combatant(fighter).and_then(|c| c.status.take()) {
+                    self.messages
+                        .push(format!("{} recovered from {}!", name, status));
+                }
+            }
+            BattleEvent::StatChanged {
+                fighter,
+                stat,
+                stages,
+            } => {
+                let change = match stages {
+                    3.. => "rose drastically",
+                    2 => "rose sharply",
+                    1 => "rose",
+                    -1 => "fell",
+                    -2 => "harshly fell",
+                    _ => "severely fell",
+                };
+                let message = format!("{}'s {} {}!", self.name(fighter), stat, change);
+                self.messages.push(message);
+            }
+            BattleEvent::HpChanged { fighter, hp, cause } => {
+                let name = self.name(fighter);
+                let previous = self.combatant(fighter).map_or(*hp, |c| c.hp);
+                let message = if *hp >= previous {
+                    format!("{} restored {} HP with {}.", name, hp - previous, cause)
+                } else {
+                    format!("{} lost {} HP to {}.", name, previous - hp, cause)
+                };
+                self.messages.push(message);
+                self.set_hp(fighter, *hp);
+            }
+            BattleEvent::AbilityActivated { fighter, ability } => {
+                let message = format!("[{}'s {}]", self.name(fighter), ability);
+                self.messages.push(message);
+            }
+            BattleEvent::ItemActivated { fighter, item } => {
+                let message = format!("{}'s {} kicked in!", self.name(fighter), item);
+                self.messages.push(message);
+            }
+            BattleEvent::Faint { fighter } => {
+                let message = format!("{}{}{} fainted!{}", BOLD, RED, self.name(fighter), RESET);
+                self.messages.push(message);
+            }
+            BattleEvent::TurnLimit { turns } => {
+                self.messages.push(format!(
+                    "{}The battle ends after {} turns! The most HP left decides.{}",
+                    BOLD, turns, RESET
+                ));
+            }
+        }
+        self.messages.len() > before
+    }
+
+    /// Announces the winner once every event was applied.
+    pub fn finish(&mut self) {
+        self.messages.push(format!(
+            "{}{}{} wins the battle!{}",
+            BOLD, GREEN, self.result.winner, RESET
+        ));
+    }
+
+    fn trainer(&self, side: usize) -> &str {
+        match side {
+            0 => &self.result.fighter1,
+            _ => &self.result.fighter2,
+        }
+    }
+
+    fn name(&self, fighter: &Fighter) -> String {
+        format!("{}'s {}", self.trainer(fighter.side), fighter.name)
+    }
+
+    fn combatant(&mut self, fighter: &Fighter) -> Option<&mut Combatant> {
+        self.combatants.get_mut(&(fighter.side, fighter.slot))
+    }
+
+    fn set_hp(&mut self, fighter: &Fighter, hp: i64) {
+        if let Some(combatant) = self.combatant(fighter) {
+            combatant.hp = hp;
+        }
+    }
+}
+
+impl<'a> fmt::Display for BattleReplay<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let separator = "=".repeat(60);
+        writeln!(f, "{}", separator)?;
+        writeln!(
+            f,
+            "{}Round {}: {} vs {}{}",
+            BOLD, self.result.round, self.result.fighter1, self.result.fighter2, RESET
+        )?;
+        writeln!(f, "{}", separator)?;
+
+        // One line per Pokemon in battle, the second trainer's side on top
+        for side in [1, 0] {
+            for (_, combatant) in self.combatants.range((side, 0)..(side + 1, 0)) {
+                writeln!(f, "{}", hp_line(self.trainer(side), combatant))?;
+            }
+            if side == 1 {
+                writeln!(f)?;
+            }
+        }
+
+        writeln!(f, "{}", "-".repeat(60))?;
+        let skip = self.messages.len().saturating_sub(REPLAY_MESSAGES);
+        for message in &self.messages[skip..] {
+            writeln!(f, "{}", message)?;
+        }
+        Ok(())
+    }
+}
+
+/// `Ash's pikachu  [#############-------]  62/95 PAR`, colored by the share
+/// of HP left.
+fn hp_line(trainer: &str, combatant: &Combatant) -> String {
+    let share = combatant.hp.max(0) as f64 / combatant.max_hp.max(1) as f64;
+    let filled = ((share * HP_BAR_WIDTH as f64).ceil() as usize).min(HP_BAR_WIDTH);
+    let color = if share > 0.5 {
+        GREEN
+    } else if share > 0.2 {
+        YELLOW
+    } else {
+        RED
+    };
+    let status = match combatant.status {
+        Some(StatusCondition::Burn) => " BRN",
+        Some(StatusCondition::Poison) => " PSN",
+        Some(StatusCondition::Paralysis) => " PAR",
+        Some(StatusCondition::Sleep) => " SLP",
+        Some(StatusCondition::Freeze) => " FRZ",
+        None => "",
+    };
+    format!(
+        "{:<30} [{}{}{}{}] {:>3}/{}{}",
+        format!("{}'s {}", trainer, combatant.name),
+        color,
+        "#".repeat(filled),
+        RESET,
+        "-".repeat(HP_BAR_WIDTH - filled),
+        combatant.hp.max(0),
+        combatant.max_hp,
+        status
+    )
+}
@@ -1,7 +1,15 @@
 use clap::Parser;
-use cli::{Cli, Commands, OutputFormat};
-use display::TournamentDisplay;
-use pokefight_core::{PokeFightError, PokeService, Result, Tournament, Trainer};
+use cli::{CacheCommand, Cli, Commands, OutputFormat};
+use display::{BattleReplay, TournamentDisplay};
+use pokefight_core::{
+    BatchOptions, CacheConfig, CachedSource, MatchResult, OfflineSource, PokeFightError,
+    PokeService, PokemonSource, Result, Roster, Tournament, TournamentResult, TypeChart,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Duration;
 
 mod cli;
 mod display;
@@ -9,22 +17,72 @@ mod display;
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Cli::parse();
-    let mut poke_service = PokeService::default();
+    let cache_config = CacheConfig::default().with_cache_only(args.cache_only);
+    let batch_options =
+        BatchOptions::default().with_request_timeout(Duration::from_secs(args.timeout));
 
     match &args.commands {
         Some(Commands::Tournament {
             names,
+            roster,
             title,
             chore,
             format,
+            concurrency,
+            pool,
+            type_chart,
+            ruleset,
+            seed,
+            level,
+            random_builds,
+            team_size,
+            strategy,
+            battle_format,
+            arena,
         }) => {
-            let mut participants: Vec<Trainer> = vec![];
-            for name in names {
-                let pokemon = poke_service.get_random_pokemon().await?;
-                let trainer = Trainer::new(name.to_string(), pokemon);
-                participants.push(trainer);
+            let pokeapi = PokeService::default().with_timeout(batch_options.request_timeout);
+            let poke_service: Box<dyn PokemonSource> = if args.offline {
+                Box::new(OfflineSource::new())
+            } else if args.no_cache {
+                Box::new(pokeapi)
+            } else {
+                Box::new(CachedSource::new(pokeapi, cache_config))
+            };
+
+            let type_chart = match type_chart {
+                Some(path) => TypeChart::from_file(path)?,
+                None => TypeChart::standard(),
+            };
+
+            let mut roster = match roster {
+                Some(path) => Roster::from_file(path)?,
+                None => Roster::default(),
+            };
+            roster.trainers.extend(names.iter().cloned());
+            for entry in &mut roster.trainers {
+                entry.strategy = entry.strategy.take().or_else(|| strategy.clone());
             }
-            let tournament = Tournament::new(participants, title, chore);
+
+            let seed = seed.unwrap_or_else(rand::random);
+            let team_size = *team_size as usize;
+            let participants = roster
+                .draft_trainers(
+                    &pool.to_draft_pool(),
+                    poke_service.as_ref(),
+                    team_size,
+                    &batch_options.with_concurrency(*concurrency),
+                    &mut StdRng::seed_from_u64(seed),
+                )
+                .await?;
+            let tournament = Tournament::new(participants, title, chore)
+                .with_type_chart(type_chart)
+                .with_damage_model(ruleset.damage_model())
+                .with_seed(seed)
+                .with_level(*level)
+                .with_random_builds(*random_builds)
+                .with_team_size(team_size)
+                .with_format(*battle_format)
+                .with_arena(arena.to_arena());
             let tournament_result = tournament.start()?;
 
             // Output based on format
@@ -42,6 +100,108 @@ async fn main() -> Result<()> {
 
             Ok(())
         }
+        Some(Commands::Replay {
+            file,
+            speed,
+            step,
+            match_number,
+        }) => run_replay(file, *speed, *step, *match_number),
+        Some(Commands::Cache { action }) => {
+            let pokeapi = PokeService::default().with_timeout(batch_options.request_timeout);
+            let cache = CachedSource::new(pokeapi, cache_config);
+            run_cache_command(&cache, action).await
+        }
         None => Err(PokeFightError::NoParticipants),
     }
 }
+
+/// Time between two events of a replay at normal speed.
+const REPLAY_DELAY: Duration = Duration::from_millis(800);
+
+/// Plays back every battle in a saved tournament, or a single saved match.
+/// Synchronous, as it only reads a file and waits on the terminal.
+fn run_replay(path: &Path, speed: f64, step: bool, match_number: Option<usize>) -> Result<()> {
+    let contents = std::fs::read_to_string(path)?;
+    let matches = match serde_json::from_str::<TournamentResult>(&contents) {
+        Ok(result) => result.matches,
+        Err(_) => vec![serde_json::from_str::<MatchResult>(&contents).map_err(|e| {
+            PokeFightError::ConfigError(format!(
+                "{} is neither a tournament nor a match: {}",
+                path.display(),
+                e
+            ))
+        })?],
+    };
+    let matches = match match_number {
+        Some(number) => {
+            let chosen = number.checked_sub(1).and_then(|index| matches.get(index));
+            vec![chosen.cloned().ok_or_else(|| {
+                PokeFightError::ConfigError(format!(
+                    "{} has no match {}, only {}",
+                    path.display(),
+                    number,
+                    matches.len()
+                ))
+            })?]
+        }
+        None => matches,
+    };
+
+    // Free passes and results saved before battles were logged have no events
+    let battles: Vec<MatchResult> = matches.into_iter().filter(|m| !m.log.is_empty()).collect();
+    if battles.is_empty() {
+        return Err(PokeFightError::ConfigError(format!(
+            "{} has no battle to replay",
+            path.display()
+        )));
+    }
+
+    for result in &battles {
+        let mut replay = BattleReplay::new(result);
+        for event in &result.log.events {
+            if replay.apply(event) {
+                show_replay(&replay, speed, step)?;
+            }
+        }
+        replay.finish();
+        show_replay(&replay, speed, step)?;
+    }
+    Ok(())
+}
+
+/// Redraws the replay, then waits for the next event.
+fn show_replay(replay: &BattleReplay<'_>, speed: f64, step: bool) -> Result<()> {
+    print!("\x1b[2J\x1b[H{}", replay);
+    if step {
+        print!("Press Enter to continue");
+        io::stdout().flush()?;
+        io::stdin().read_line(&mut String::new())?;
+    } else {
+        io::stdout().flush()?;
+        std::thread::sleep(REPLAY_DELAY.div_f64(speed));
+    }
+    Ok(())
+}
+
+async fn run_cache_command(cache: &CachedSource<PokeService>, action: &CacheCommand) -> Result<()> {
+    match action {
+        CacheCommand::Warm { from, to } => {
+            for id in *from..=*to {
+                let pokemon = cache.by_id(id).await?;
+                println!("#{:<4} {}", id, pokemon.get_name());
+            }
+            println!("Cached Pokemon #{} to #{}", from, to);
+        }
+        CacheCommand::Info => {
+            let stats = cache.stats().await?;
+            println!("Location: {}", cache.config().dir.display());
+            println!("Entries:  {} ({} expired)", stats.entries, stats.expired);
+            println!("Size:     {} KiB", stats.size_bytes.div_ceil(1024));
+        }
+        CacheCommand::Clear => {
+            let removed = cache.clear().await?;
+            println!("Removed {} cached Pokemon", removed);
+        }
+    }
+    Ok(())
+}
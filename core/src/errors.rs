@@ -8,9 +8,30 @@ pub enum PokeFightError {
     #[error("Pokemon not found with ID: {0}")]
     PokemonNotFound(u32),
 
+    #[error("Pokemon not found with name: {0}")]
+    PokemonNameNotFound(String),
+
+    #[error("Pokemon {0} is not in the cache")]
+    NotCached(String),
+
+    #[error("{0} is not allowed by this tournament's draft pool")]
+    PokemonNotInPool(String),
+
+    #[error("{0} has already been picked by another trainer")]
+    DuplicatePokemon(String),
+
+    #[error("Draft pool has no eligible Pokemon left")]
+    PoolExhausted,
+
     #[error("Invalid Pokemon ID: {0}")]
     InvalidPokemonId(u32),
 
+    #[error("Could not fetch a Pokemon for {trainer}: {source}")]
+    DraftFailed {
+        trainer: String,
+        source: Box<PokeFightError>,
+    },
+
     #[error("Tournament error: {0}")]
     TournamentError(String),
 
@@ -20,11 +41,38 @@ pub enum PokeFightError {
     #[error("Invalid number of participants: {0}. Must be at least 2.")]
     InvalidParticipantCount(usize),
 
+    #[error("Invalid team size: {0}. Teams have 1 to 6 Pokemon.")]
+    InvalidTeamSize(usize),
+
+    #[error("Invalid level: {0}. Pokemon fight at level 1 to 100.")]
+    InvalidLevel(u8),
+
     #[error("Battle error: {0}")]
     BattleError(String),
 
+    #[error("Unknown held item: {0}")]
+    UnknownItem(String),
+
+    #[error("Unknown battle strategy: {0}")]
+    UnknownStrategy(String),
+
+    #[error("Invalid configuration: {0}")]
+    ConfigError(String),
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 }
 
+impl PokeFightError {
+    /// Whether the same request might succeed if retried (timeouts and 5xx responses).
+    pub fn is_transient(&self) -> bool {
+        match self {
+            PokeFightError::PokeApiError(e) => {
+                e.is_timeout() || e.status().is_some_and(|s| s.is_server_error())
+            }
+            _ => false,
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, PokeFightError>;
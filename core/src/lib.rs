@@ -4,8 +4,30 @@ pub mod pokeservice;
 
 pub use errors::{PokeFightError, Result};
 pub use models::{
-    pokemon::{BattlePokemon, Pokemon},
+    battle::{
+        abilities::Ability,
+        field::{Arena, Field, Terrain, Weather},
+        items::HeldItem,
+        log::{BattleEvent, BattleLog, Fighter},
+        strategy::BattleStrategy,
+        Action, AttackReport, BattleFormat, BattleRules, Position, RoundReport, Turn,
+    },
+    damage::{ClassicDamage, DamageModel, MainSeriesDamage, Ruleset},
+    moves::{DamageClass, Move, Movedex},
+    pokemon::{BattlePokemon, Pokemon, PokemonChoice},
+    roster::{Roster, RosterEntry},
+    stats::{Nature, PokemonBuild, StatValues},
+    status::StatusCondition,
     tournament::{MatchResult, Tournament, TournamentResult},
     trainer::Trainer,
+    type_chart::TypeChart,
+};
+pub use pokeservice::{
+    batch::BatchOptions,
+    cache::{CacheConfig, CacheStats, CachedSource},
+    fixture::FixtureSource,
+    http::PokeService,
+    offline::OfflineSource,
+    pool::DraftPool,
+    PokemonSource,
 };
-pub use pokeservice::PokeService;
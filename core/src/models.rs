@@ -1,5 +1,12 @@
 pub mod battle;
+pub mod damage;
+pub mod moves;
 pub mod pokemon;
+pub mod roster;
+pub mod stages;
+pub mod stats;
+pub mod status;
 pub mod test_utils;
 pub mod tournament;
 pub mod trainer;
+pub mod type_chart;
@@ -1,7 +1,105 @@
+use std::fmt;
 use std::marker::PhantomData;
+use std::str::FromStr;
 
+pub mod abilities;
+pub mod field;
+pub mod items;
+pub mod log;
+pub mod strategy;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use self::field::{Arena, Field, Terrain, Weather};
+use self::items::HeldItem;
+use self::log::{BattleEvent, Fighter};
+use self::strategy::BattleStrategy;
+use super::damage::{DamageModel, DamageRoll, Ruleset};
+use super::moves::{Move, Movedex, Targeting};
 use super::pokemon::BattlePokemon;
+use super::stages::accuracy_multiplier;
+use super::status::StatusCondition;
 use super::trainer::Trainer;
+use super::type_chart::TypeChart;
+
+/// Everything a battle needs to know about how damage is dealt.
+pub struct BattleRules {
+    pub type_chart: TypeChart,
+    pub damage_model: Box<dyn DamageModel>,
+    pub movedex: Movedex,
+    /// Weather and terrain every battle starts with.
+    pub arena: Arena,
+    /// How many Pokemon each trainer has in battle at once.
+    pub format: BattleFormat,
+    /// Rounds after which a battle ends even if both sides have Pokemon left.
+    pub max_rounds: usize,
+}
+
+/// Enough rounds for six Pokemon a side to faint one by one, while two
+/// walls healing faster than they hurt each other cannot battle forever.
+pub const MAX_ROUNDS: usize = 300;
+
+impl Default for BattleRules {
+    fn default() -> Self {
+        Self {
+            type_chart: TypeChart::standard(),
+            damage_model: Ruleset::default().damage_model(),
+            movedex: Movedex::standard(),
+            arena: Arena::default(),
+            format: BattleFormat::default(),
+            max_rounds: MAX_ROUNDS,
+        }
+    }
+}
+
+/// Selects singles or doubles, e.g. from a CLI flag or a web request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BattleFormat {
+    /// One Pokemon per trainer at a time.
+    #[default]
+    Singles,
+    /// Two Pokemon per trainer at a time, spread moves hit both opponents.
+    Doubles,
+}
+
+impl BattleFormat {
+    /// Pokemon each trainer has in battle at once, if its team is big enough.
+    pub fn active_slots(&self) -> usize {
+        match self {
+            BattleFormat::Singles => 1,
+            BattleFormat::Doubles => 2,
+        }
+    }
+}
+
+impl fmt::Display for BattleFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BattleFormat::Singles => "singles",
+            BattleFormat::Doubles => "doubles",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for BattleFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "singles" => Ok(BattleFormat::Singles),
+            "doubles" => Ok(BattleFormat::Doubles),
+            other => Err(format!(
+                "unknown battle format '{}', expected 'singles' or 'doubles'",
+                other
+            )),
+        }
+    }
+}
 
 // State markers (zero-sized types)
 pub struct Ready;
@@ -10,52 +108,199 @@ pub struct Finished;
 
 pub struct Battle<'a, State = Ready> {
     participants: BattleParticipants<'a>,
+    rng: StdRng,
+    rounds_played: usize,
+    field: Field,
+    /// Side that won, decided when the battle finishes.
+    winner: usize,
     state: PhantomData<State>,
 }
 
+/// Both sides of the battle, the first trainer's at index 0.
 pub struct BattleParticipants<'a> {
-    faster: BattleParticipant<'a>,
-    slower: BattleParticipant<'a>,
+    sides: [BattleParticipant<'a>; 2],
+}
+
+/// Where a Pokemon stands in battle: its trainer's side, 0 for the first
+/// trainer, and its slot on that side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub side: usize,
+    pub slot: usize,
+}
+
+impl<'a> BattleParticipants<'a> {
+    fn get(&self, position: Position) -> &BattlePokemon<'a> {
+        self.sides[position.side].active(position.slot)
+    }
+
+    /// Every slot on both sides, side by side.
+    fn slots(&self) -> Vec<Position> {
+        self.sides
+            .iter()
+            .enumerate()
+            .flat_map(|(side, participant)| {
+                (0..participant.active.len()).map(move |slot| Position { side, slot })
+            })
+            .collect()
+    }
+
+    /// Positions of the active Pokemon that have not fainted, side by side.
+    fn positions(&self) -> Vec<Position> {
+        self.slots()
+            .into_iter()
+            .filter(|&position| !self.get(position).is_fainted())
+            .collect()
+    }
+
+    /// Active opponents of the Pokemon at `position` that have not fainted.
+    fn opponents(&self, position: Position) -> Vec<Position> {
+        self.positions()
+            .into_iter()
+            .filter(|other| other.side != position.side)
+            .collect()
+    }
+
+    /// Active partners of the Pokemon at `position` that have not fainted.
+    fn allies(&self, position: Position) -> Vec<Position> {
+        self.positions()
+            .into_iter()
+            .filter(|other| other.side == position.side && other.slot != position.slot)
+            .collect()
+    }
+
+    /// The Pokemon at each of the distinct `positions`, in the same order.
+    fn pokemon_mut(&mut self, positions: &[Position]) -> Vec<&mut BattlePokemon<'a>> {
+        let mut in_battle = Vec::new();
+        for (side, participant) in self.sides.iter_mut().enumerate() {
+            let active = &participant.active;
+            for (index, pokemon) in participant.team.iter_mut().enumerate() {
+                if let Some(slot) = active.iter().position(|&i| i == index) {
+                    in_battle.push((Position { side, slot }, pokemon));
+                }
+            }
+        }
+        positions
+            .iter()
+            .map(|position| {
+                let at = in_battle
+                    .iter()
+                    .position(|(p, _)| p == position)
+                    .expect("positions should be distinct and in battle");
+                in_battle.swap_remove(at).1
+            })
+            .collect()
+    }
 }
 
 pub struct BattleParticipant<'a> {
-    pokemon: BattlePokemon<'a>,
+    team: Vec<BattlePokemon<'a>>,
+    /// Team indices of the Pokemon in battle, one per slot.
+    active: Vec<usize>,
+    /// Team indices of the Pokemon that entered the battle, in order.
+    fielded: Vec<usize>,
+    /// Per slot, whether the Pokemon had a turn since it came in. Only then
+    /// can it switch out, so two trainers cannot keep swapping forever.
+    settled: Vec<bool>,
     trainer: &'a Trainer,
+    strategy: &'a dyn BattleStrategy,
+}
+
+impl<'a> BattleParticipant<'a> {
+    /// The first `slots` team members lead, or the whole team if it is
+    /// smaller.
+    fn new(trainer: &'a Trainer, movedex: &Movedex, slots: usize) -> Self {
+        let team: Vec<_> = trainer
+            .get_battle_team()
+            .into_iter()
+            .map(|pokemon| {
+                let moves = movedex.move_set(pokemon.get_pokemon());
+                pokemon.with_moves(moves)
+            })
+            .collect();
+        let active: Vec<usize> = (0..slots.clamp(1, team.len())).collect();
+        Self {
+            team,
+            fielded: active.clone(),
+            settled: vec![false; active.len()],
+            active,
+            trainer,
+            strategy: trainer.get_strategy(),
+        }
+    }
+
+    fn active(&self, slot: usize) -> &BattlePokemon<'a> {
+        &self.team[self.active[slot]]
+    }
+
+    fn active_mut(&mut self, slot: usize) -> &mut BattlePokemon<'a> {
+        &mut self.team[self.active[slot]]
+    }
+
+    /// Team members that can still come in, with their team index.
+    fn bench(&self) -> impl Iterator<Item = (usize, &BattlePokemon<'a>)> {
+        self.team
+            .iter()
+            .enumerate()
+            .filter(|(index, pokemon)| !self.active.contains(index) && !pokemon.is_fainted())
+    }
+
+    /// Whether the whole team has fainted.
+    fn is_defeated(&self) -> bool {
+        self.team.iter().all(BattlePokemon::is_fainted)
+    }
+
+    /// HP the whole team has left, and its max HP.
+    fn team_hp(&self) -> (i64, i64) {
+        self.team.iter().fold((0, 0), |(hp, max_hp), pokemon| {
+            (hp + pokemon.get_current_hp(), max_hp + pokemon.get_max_hp())
+        })
+    }
+
+    /// Replaces the Pokemon in `slot` with the team member at `index`.
+    fn switch_to(&mut self, slot: usize, index: usize, report: &mut RoundReport) {
+        self.active_mut(slot).switch_out();
+        self.active[slot] = index;
+        self.settled[slot] = false;
+        if !self.fielded.contains(&index) {
+            self.fielded.push(index);
+        }
+        report.switches.push((
+            self.trainer.get_name().to_string(),
+            self.active(slot).get_name().to_string(),
+        ));
+    }
+
+    fn fielded_names(&self) -> Vec<String> {
+        self.fielded
+            .iter()
+            .map(|&index| self.team[index].get_name().to_string())
+            .collect()
+    }
 }
 
 // Battle construction and state transitions
 impl<'a> Battle<'a, Ready> {
-    pub fn new(trainer1: &'a Trainer, trainer2: &'a Trainer) -> Self {
-        let p1 = trainer1.get_pokemon();
-        let p2 = trainer2.get_pokemon();
-
-        // Order by speed at construction - faster attacks first
-        let (faster, slower) = if p1.get_speed() >= p2.get_speed() {
-            (
-                BattleParticipant {
-                    pokemon: p1,
-                    trainer: trainer1,
-                },
-                BattleParticipant {
-                    pokemon: p2,
-                    trainer: trainer2,
-                },
-            )
-        } else {
-            (
-                BattleParticipant {
-                    pokemon: p2,
-                    trainer: trainer2,
-                },
-                BattleParticipant {
-                    pokemon: p1,
-                    trainer: trainer1,
-                },
-            )
-        };
+    /// Every random roll in the battle is derived from `seed`. Move sets
+    /// are picked from the rules' movedex.
+    pub fn new(
+        trainer1: &'a Trainer,
+        trainer2: &'a Trainer,
+        rules: &BattleRules,
+        seed: u64,
+    ) -> Self {
+        let slots = rules.format.active_slots();
+        let first = BattleParticipant::new(trainer1, &rules.movedex, slots);
+        let second = BattleParticipant::new(trainer2, &rules.movedex, slots);
 
         Self {
-            participants: BattleParticipants { faster, slower },
+            participants: BattleParticipants {
+                sides: [first, second],
+            },
+            rng: StdRng::seed_from_u64(seed),
+            rounds_played: 0,
+            field: Field::new(&rules.arena),
+            winner: 0,
             state: PhantomData,
         }
     }
@@ -63,6 +308,10 @@ impl<'a> Battle<'a, Ready> {
     pub fn begin(self) -> Battle<'a, InProgress> {
         Battle {
             participants: self.participants,
+            rng: self.rng,
+            rounds_played: self.rounds_played,
+            field: self.field,
+            winner: self.winner,
             state: PhantomData,
         }
     }
@@ -73,27 +322,156 @@ impl<'a> Battle<'a, InProgress> {
         RoundIterator { battle: self }
     }
 
-    pub fn finish(self) -> Battle<'a, Finished> {
+    pub fn get_field(&self) -> &Field {
+        &self.field
+    }
+
+    pub fn finish(mut self) -> Battle<'a, Finished> {
+        let winner = self.decide_winner();
         Battle {
             participants: self.participants,
+            rng: self.rng,
+            rounds_played: self.rounds_played,
+            field: self.field,
+            winner,
             state: PhantomData,
         }
     }
+
+    /// The side with Pokemon left standing. When the round limit ended the
+    /// battle, the side with the larger share of its team's HP left wins. The
+    /// battle's seed breaks a tie, and decides when both sides went down in
+    /// the same round.
+    fn decide_winner(&mut self) -> usize {
+        let [first, second] = &self.participants.sides;
+        match (first.is_defeated(), second.is_defeated()) {
+            (true, true) => self.rng.random_range(0..2),
+            (true, false) => 1,
+            (false, true) => 0,
+            _ => {
+                let (first_hp, first_max) = first.team_hp();
+                let (second_hp, second_max) = second.team_hp();
+                match (first_hp * second_max).cmp(&(second_hp * first_max)) {
+                    std::cmp::Ordering::Greater => 0,
+                    std::cmp::Ordering::Less => 1,
+                    std::cmp::Ordering::Equal => self.rng.random_range(0..2),
+                }
+            }
+        }
+    }
 }
 
 impl<'a> Battle<'a, Finished> {
     pub fn winner(&self) -> &'a Trainer {
-        if self.participants.faster.pokemon.is_fainted() {
-            self.participants.slower.trainer
-        } else {
-            self.participants.faster.trainer
-        }
+        self.participants.sides[self.winner].trainer
     }
     pub fn looser(&self) -> &'a Trainer {
-        if self.participants.faster.pokemon.is_fainted() {
-            self.participants.faster.trainer
-        } else {
-            self.participants.slower.trainer
+        self.participants.sides[1 - self.winner].trainer
+    }
+
+    /// Whether the round limit ended the battle before either side ran out
+    /// of Pokemon.
+    pub fn timed_out(&self) -> bool {
+        !self
+            .participants
+            .sides
+            .iter()
+            .any(BattleParticipant::is_defeated)
+    }
+
+    /// Names of the team members each trainer sent into battle, in the
+    /// order they entered: the first trainer's, then the second's.
+    pub fn fielded(&self) -> (Vec<String>, Vec<String>) {
+        let [first, second] = &self.participants.sides;
+        (first.fielded_names(), second.fielded_names())
+    }
+}
+
+/// What happened when a Pokemon used a move on one of its targets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttackReport {
+    pub attacker: String,
+    /// Pokemon the move was aimed at, the attacker itself for moves without
+    /// a target like Swords Dance.
+    pub defender: String,
+    pub move_name: String,
+    pub hit: bool,
+    pub critical: bool,
+    pub damage: i64,
+    /// Status condition the move put on the defender.
+    pub inflicted: Option<StatusCondition>,
+    /// Stat stages the move changed, as (pokemon, stat, stages).
+    pub stat_changes: Vec<(String, String, i8)>,
+}
+
+/// Everything that happened in one round, in order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RoundReport {
+    /// One report per target, so spread moves show up once for each.
+    pub attacks: Vec<AttackReport>,
+    /// Pokemon that could not move because of their status condition.
+    pub immobilized: Vec<(String, StatusCondition)>,
+    /// HP lost to burn or poison at the end of the round.
+    pub residual_damage: Vec<(String, StatusCondition, i64)>,
+    /// Abilities that had an effect, as (pokemon, ability).
+    pub abilities: Vec<(String, String)>,
+    /// Held items that had an effect, as (pokemon, item).
+    pub items: Vec<(String, String)>,
+    /// HP lost to sandstorm or hail at the end of the round.
+    pub weather_damage: Vec<(String, Weather, i64)>,
+    /// HP restored by grassy terrain at the end of the round.
+    pub terrain_healing: Vec<(String, Terrain, i64)>,
+    /// Weather that ran out at the end of the round.
+    pub weather_ended: Option<Weather>,
+    /// Terrain that ran out at the end of the round.
+    pub terrain_ended: Option<Terrain>,
+    /// Pokemon sent in, as (trainer, pokemon). Switches come before any
+    /// move, replacements for fainted Pokemon at the end of the round.
+    pub switches: Vec<(String, String)>,
+    /// Everything above as typed events, in the order it happened. See
+    /// [`log::BattleLog`].
+    pub events: Vec<BattleEvent>,
+}
+
+impl RoundReport {
+    pub fn critical_hits(&self) -> usize {
+        self.attacks.iter().filter(|a| a.critical).count()
+    }
+
+    pub fn misses(&self) -> usize {
+        self.attacks.iter().filter(|a| !a.hit).count()
+    }
+
+    fn log(&mut self, event: BattleEvent) {
+        self.events.push(event);
+    }
+
+    /// Logs the HP `pokemon` has left after `cause` changed it, and its
+    /// faint if that took its last HP.
+    fn log_hp(&mut self, position: Position, pokemon: &BattlePokemon, cause: &str) {
+        self.log(BattleEvent::HpChanged {
+            fighter: Fighter::new(position, pokemon.get_name()),
+            hp: pokemon.get_current_hp(),
+            cause: cause.to_string(),
+        });
+        self.log_faint(position, pokemon);
+    }
+
+    /// Records that the ability of `pokemon` had an effect.
+    fn ability_activated(&mut self, position: Position, pokemon: &BattlePokemon, ability: &str) {
+        self.abilities
+            .push((pokemon.get_name().to_string(), ability.to_string()));
+        self.log(BattleEvent::AbilityActivated {
+            fighter: Fighter::new(position, pokemon.get_name()),
+            ability: ability.to_string(),
+        });
+    }
+
+    fn log_faint(&mut self, position: Position, pokemon: &BattlePokemon) {
+        if pokemon.is_fainted() {
+            self.log(BattleEvent::Faint {
+                fighter: Fighter::new(position, pokemon.get_name()),
+            });
         }
     }
 }
@@ -104,48 +482,1683 @@ pub struct RoundIterator<'a, 'b> {
 }
 
 impl<'a, 'b> RoundIterator<'a, 'b> {
-    pub fn execute_round(&mut self, effectiveness: impl Fn(&str, &str) -> f64) -> Option<()> {
-        // Faster pokemon attacks first
-        let damage = calculate_damage(
-            &self.battle.participants.faster.pokemon,
-            &self.battle.participants.slower.pokemon,
-            &effectiveness,
-        );
-        self.battle.participants.slower.pokemon.take_damage(damage);
-
-        // Check if slower pokemon fainted
-        if self.battle.participants.slower.pokemon.is_fainted() {
+    /// Plays one round, or returns `None` once a whole team has fainted or
+    /// the rules' round limit is reached.
+    ///
+    /// Every Pokemon in battle gets an action, picked before anyone acts.
+    /// Switches go first, then higher priority moves, then the Pokemon with
+    /// the higher effective speed. Fainted Pokemon are replaced at the end
+    /// of the round.
+    pub fn execute_round(&mut self, rules: &BattleRules) -> Option<RoundReport> {
+        let participants = &mut self.battle.participants;
+        let field = &mut self.battle.field;
+        let rng = &mut self.battle.rng;
+        if participants
+            .sides
+            .iter()
+            .any(BattleParticipant::is_defeated)
+            || self.battle.rounds_played >= rules.max_rounds
+        {
             return None;
         }
+        let mut report = RoundReport::default();
 
-        // Slower pokemon counter-attacks
-        let damage = calculate_damage(
-            &self.battle.participants.slower.pokemon,
-            &self.battle.participants.faster.pokemon,
-            &effectiveness,
-        );
-        self.battle.participants.faster.pokemon.take_damage(damage);
+        // The leads enter the battle before the first round, fastest first
+        if self.battle.rounds_played == 0 {
+            let mut leads = participants.positions();
+            leads.sort_by_key(|&position| -participants.get(position).get_speed());
+            for position in leads {
+                switch_in(participants, position, &mut report);
+            }
+        }
+        self.battle.rounds_played += 1;
+        report.log(BattleEvent::TurnStart {
+            turn: self.battle.rounds_played,
+        });
 
-        // Check if faster pokemon fainted
-        if self.battle.participants.faster.pokemon.is_fainted() {
-            return None;
+        // Partners don't send in the same team member
+        let mut actions: Vec<(Position, Action)> = Vec::new();
+        for position in participants.positions() {
+            let taken: Vec<usize> = actions
+                .iter()
+                .filter_map(|(other, action)| match action {
+                    Action::Switch(index) if other.side == position.side => Some(*index),
+                    _ => None,
+                })
+                .collect();
+            let turn = Turn::new(participants, position, &taken, rules, field);
+            let action = participants.sides[position.side]
+                .strategy
+                .choose_action(&turn, rng);
+            actions.push((position, action));
+        }
+        for &(position, action) in &actions {
+            if let Action::Switch(index) = action
+                && participants.sides[position.side]
+                    .bench()
+                    .any(|(benched, _)| benched == index)
+            {
+                participants.sides[position.side].switch_to(position.slot, index, &mut report);
+                switch_in(participants, position, &mut report);
+            }
+        }
+
+        let speeds: Vec<(i64, i64)> = actions
+            .iter()
+            .map(|&(position, action)| turn_speed(participants.get(position), action.move_choice()))
+            .collect();
+        let order: Vec<Position> = turn_order(&speeds, rng)
+            .into_iter()
+            .map(|index| actions[index].0)
+            .collect();
+
+        for &position in &order {
+            let Some(&(_, Action::Move { choice, target })) =
+                actions.iter().find(|(p, _)| *p == position)
+            else {
+                continue;
+            };
+            // Fainted Pokemon don't act, and neither does anyone once one
+            // side has nobody left standing, e.g. after Life Orb recoil
+            let opponents = participants.opponents(position);
+            if participants.get(position).is_fainted() || opponents.is_empty() {
+                continue;
+            }
+            let targets = match move_targeting(participants.get(position), choice) {
+                // A target that fainted is replaced by another opponent
+                Targeting::Single if opponents.contains(&target) => vec![target],
+                Targeting::Single => vec![opponents[0]],
+                Targeting::AllOpponents => opponents,
+                Targeting::AllOthers => participants
+                    .positions()
+                    .into_iter()
+                    .filter(|&other| other != position)
+                    .collect(),
+                Targeting::Ally if participants.allies(position).is_empty() => continue,
+                Targeting::Ally => participants.allies(position),
+                Targeting::User => vec![],
+            };
+
+            let positions = [&[position], &targets[..]].concat();
+            let mut involved: Vec<_> = positions
+                .iter()
+                .copied()
+                .zip(participants.pokemon_mut(&positions))
+                .collect();
+            let ((_, attacker), defenders) = involved
+                .split_first_mut()
+                .expect("the attacker should be in battle");
+            take_turn(
+                (position, attacker),
+                defenders,
+                choice,
+                rules,
+                field,
+                rng,
+                &mut report,
+            );
+        }
+
+        // Burn and poison hurt at the end of the round, in action order,
+        // until one side has nobody left standing
+        for &position in &order {
+            if participants.opponents(position).is_empty() {
+                break;
+            }
+            let pokemon = participants.sides[position.side].active_mut(position.slot);
+            if pokemon.is_fainted() {
+                continue;
+            }
+            let damage = pokemon.take_residual_damage();
+            if let Some(status) = pokemon.get_status()
+                && damage > 0
+            {
+                report
+                    .residual_damage
+                    .push((pokemon.get_name().to_string(), status, damage));
+                report.log_hp(position, pokemon, &status.to_string());
+            }
+        }
+
+        // Then the weather chips away and the terrain heals
+        for &position in &order {
+            let pokemon = participants.sides[position.side].active_mut(position.slot);
+            if pokemon.is_fainted() {
+                continue;
+            }
+            if let Some(weather) = field.get_weather() {
+                let damage = weather.chip_damage(pokemon);
+                if damage > 0 {
+                    pokemon.take_damage(damage);
+                    report
+                        .weather_damage
+                        .push((pokemon.get_name().to_string(), weather, damage));
+                    report.log_hp(position, pokemon, &weather.to_string());
+                }
+            }
+            if let Some(terrain) = field.get_terrain() {
+                let healed = pokemon.heal(terrain.healing(pokemon));
+                if healed > 0 {
+                    report
+                        .terrain_healing
+                        .push((pokemon.get_name().to_string(), terrain, healed));
+                    report.log_hp(position, pokemon, &terrain.to_string());
+                }
+            }
+        }
+
+        // Abilities like Speed Boost and items like Leftovers kick in once
+        // the round is over
+        for &position in &order {
+            let pokemon = participants.sides[position.side].active_mut(position.slot);
+            if pokemon.is_fainted() {
+                continue;
+            }
+            if let Some(ability) = pokemon.get_ability()
+                && ability.on_end_of_turn(pokemon)
+            {
+                report.ability_activated(position, pokemon, ability.name());
+            }
+            trigger_item((position, pokemon), &mut report, |item, owner| {
+                item.on_end_of_turn(owner)
+            });
+            trigger_item((position, pokemon), &mut report, |item, owner| {
+                item.on_update(owner)
+            });
+        }
+        for (position, action) in actions {
+            participants.sides[position.side].settled[position.slot] |=
+                matches!(action, Action::Move { .. });
+        }
+
+        (report.weather_ended, report.terrain_ended) = field.end_round();
+
+        // Fainted Pokemon make way for the team member their trainer's
+        // strategy picks. Every slot is filled before any newcomer's ability
+        // kicks in.
+        let mut replaced = Vec::new();
+        for position in participants.slots() {
+            if !participants.get(position).is_fainted() {
+                continue;
+            }
+            let turn = Turn::new(participants, position, &[], rules, field);
+            let replacement = participants.sides[position.side]
+                .strategy
+                .choose_replacement(&turn, rng)
+                .filter(|&index| turn.bench().iter().any(|&(benched, _)| benched == index));
+            if let Some(index) = replacement {
+                participants.sides[position.side].switch_to(position.slot, index, &mut report);
+                replaced.push(position);
+            }
+        }
+        for position in replaced {
+            switch_in(participants, position, &mut report);
+        }
+        Some(report)
+    }
+}
+
+/// What a Pokemon in battle does with its turn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    /// Use the move at index `choice`, or struggle when `None`, aimed at the
+    /// opponent at `target` if the move hits a single Pokemon.
+    Move {
+        choice: Option<usize>,
+        target: Position,
+    },
+    /// Send in the team member at this index.
+    Switch(usize),
+}
+
+impl Action {
+    fn move_choice(&self) -> Option<usize> {
+        match self {
+            Action::Move { choice, .. } => *choice,
+            Action::Switch(_) => None,
+        }
+    }
+}
+
+/// What a [`BattleStrategy`] gets to see when it decides for the Pokemon at
+/// one position.
+pub struct Turn<'t, 'a> {
+    participants: &'t BattleParticipants<'a>,
+    position: Position,
+    /// Team members the Pokemon's partners already picked to switch to.
+    taken: &'t [usize],
+    rules: &'t BattleRules,
+    field: &'t Field,
+}
+
+impl<'t, 'a> Turn<'t, 'a> {
+    fn new(
+        participants: &'t BattleParticipants<'a>,
+        position: Position,
+        taken: &'t [usize],
+        rules: &'t BattleRules,
+        field: &'t Field,
+    ) -> Self {
+        Self {
+            participants,
+            position,
+            taken,
+            rules,
+            field,
+        }
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// The Pokemon to decide for.
+    pub fn pokemon(&self) -> &'t BattlePokemon<'a> {
+        self.participants.get(self.position)
+    }
+
+    /// Opponents in battle that have not fainted.
+    pub fn opponents(&self) -> Vec<(Position, &'t BattlePokemon<'a>)> {
+        self.participants
+            .opponents(self.position)
+            .into_iter()
+            .map(|opponent| (opponent, self.participants.get(opponent)))
+            .collect()
+    }
+
+    /// Team members that can come in, with their team index. Members a
+    /// partner is already switching to are left out.
+    pub fn bench(&self) -> Vec<(usize, &'t BattlePokemon<'a>)> {
+        self.participants.sides[self.position.side]
+            .bench()
+            .filter(|(index, _)| !self.taken.contains(index))
+            .collect()
+    }
+
+    /// Whether the Pokemon had a turn since it came in. Only then can it
+    /// switch out, so two trainers cannot keep swapping forever.
+    pub fn can_switch(&self) -> bool {
+        self.participants.sides[self.position.side].settled[self.position.slot]
+    }
+
+    pub fn rules(&self) -> &'t BattleRules {
+        self.rules
+    }
+
+    pub fn field(&self) -> &'t Field {
+        self.field
+    }
+}
+
+/// Team member on the bench with the best matchup against the opponents,
+/// the first of equally good ones.
+fn best_switch(turn: &Turn) -> Option<(usize, f64)> {
+    let opponents: Vec<&BattlePokemon> = turn.opponents().into_iter().map(|(_, o)| o).collect();
+    turn.bench()
+        .into_iter()
+        .map(|(index, pokemon)| (index, matchup(pokemon, &opponents, turn.rules, turn.field)))
+        .min_by(|(_, a), (_, b)| b.total_cmp(a))
+}
+
+/// How `pokemon` fares against `opponents` on average, between -1 and 1:
+/// the share of an opponent's HP its best attack takes, minus the share of
+/// its own HP that opponent's best attack takes.
+fn matchup(
+    pokemon: &BattlePokemon,
+    opponents: &[&BattlePokemon],
+    rules: &BattleRules,
+    field: &Field,
+) -> f64 {
+    if opponents.is_empty() {
+        return 0.0;
+    }
+    let total: f64 = opponents
+        .iter()
+        .map(|opponent| {
+            threat(pokemon, opponent, rules, field) - threat(opponent, pokemon, rules, field)
+        })
+        .sum();
+    total / opponents.len() as f64
+}
+
+/// Share of the defender's remaining HP the attacker's best attack is
+/// expected to take, at most 1.
+fn threat(
+    attacker: &BattlePokemon,
+    defender: &BattlePokemon,
+    rules: &BattleRules,
+    field: &Field,
+) -> f64 {
+    let best = best_attack(attacker, defender, rules, field);
+    (best / defender.get_current_hp().max(1) as f64).min(1.0)
+}
+
+/// Damage the attacker's best attack on the defender is expected to deal,
+/// struggle if no damaging move has PP left.
+fn best_attack(
+    attacker: &BattlePokemon,
+    defender: &BattlePokemon,
+    rules: &BattleRules,
+    field: &Field,
+) -> f64 {
+    let usable: Vec<&Move> = attacker
+        .get_moves()
+        .iter()
+        .filter(|slot| slot.pp > 0 && slot.known_move.is_damaging())
+        .map(|slot| &slot.known_move)
+        .collect();
+    if usable.is_empty() {
+        expected_damage(attacker, defender, &Move::struggle(), rules, field)
+    } else {
+        usable
+            .into_iter()
+            .map(|m| expected_damage(attacker, defender, m, rules, field))
+            .fold(0.0, f64::max)
+    }
+}
+
+/// Logs the Pokemon at `position` entering the battle and runs the
+/// on-switch-in hook of its ability against every opponent in battle.
+fn switch_in(participants: &mut BattleParticipants, position: Position, report: &mut RoundReport) {
+    let pokemon = participants.get(position);
+    report.log(BattleEvent::SwitchIn {
+        fighter: Fighter::new(position, pokemon.get_name()),
+        hp: pokemon.get_current_hp(),
+        max_hp: pokemon.get_max_hp(),
+    });
+    let Some(ability) = pokemon.get_ability() else {
+        return;
+    };
+    let mut triggered = false;
+    for opponent in participants.opponents(position) {
+        let mut pair = participants.pokemon_mut(&[position, opponent]);
+        let [pokemon, opponent] = &mut pair[..] else {
+            unreachable!("two positions give two Pokemon");
+        };
+        triggered |= ability.on_switch_in(pokemon, opponent);
+    }
+    if triggered {
+        report.ability_activated(position, participants.get(position), ability.name());
+    }
+}
+
+/// Runs `hook` on the held item of the Pokemon at `position` and records the
+/// item if it had an effect, along with any HP it restored or took. Single-use
+/// items are consumed by that.
+fn trigger_item(
+    (position, pokemon): (Position, &mut BattlePokemon),
+    report: &mut RoundReport,
+    hook: impl FnOnce(&dyn HeldItem, &mut BattlePokemon) -> bool,
+) {
+    let hp = pokemon.get_current_hp();
+    let status = pokemon.get_status();
+    if let Some(item) = pokemon.get_item()
+        && hook(item, pokemon)
+    {
+        report
+            .items
+            .push((pokemon.get_name().to_string(), item.name().to_string()));
+        report.log(BattleEvent::ItemActivated {
+            fighter: Fighter::new(position, pokemon.get_name()),
+            item: item.name().to_string(),
+        });
+        if pokemon.get_current_hp() != hp {
+            report.log_hp(position, pokemon, item.name());
+        }
+        if status.is_some() && pokemon.get_status().is_none() {
+            report.log(BattleEvent::StatusCured {
+                fighter: Fighter::new(position, pokemon.get_name()),
+            });
+        }
+        if item.is_single_use() {
+            pokemon.consume_item();
+        }
+    }
+}
+
+/// Indices of the combatants in the order they act, given the (priority,
+/// effective speed) of each.
+///
+/// The higher move priority goes first, then the higher effective speed.
+/// Combatants tied on both are put in random order.
+fn turn_order(speeds: &[(i64, i64)], rng: &mut dyn RngCore) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..speeds.len()).collect();
+    order.sort_by(|&a, &b| speeds[b].cmp(&speeds[a]));
+    for tied in order.chunk_by_mut(|&a, &b| speeds[a] == speeds[b]) {
+        if tied.len() > 1 {
+            tied.shuffle(rng);
+        }
+    }
+    order
+}
+
+/// Move priority and effective speed of `pokemon` using the move at
+/// `choice`, which decide when it acts.
+fn turn_speed(pokemon: &BattlePokemon, choice: Option<usize>) -> (i64, i64) {
+    let priority = choice
+        .and_then(|index| pokemon.get_moves().get(index))
+        .map_or(0, |slot| slot.known_move.get_priority());
+    (priority, pokemon.get_speed())
+}
+
+/// Who the move at `choice` is aimed at. Struggle hits a single Pokemon.
+fn move_targeting(pokemon: &BattlePokemon, choice: Option<usize>) -> Targeting {
+    choice
+        .and_then(|index| pokemon.get_moves().get(index))
+        .filter(|slot| slot.pp > 0)
+        .map_or(Targeting::Single, |slot| slot.known_move.get_targeting())
+}
+
+/// Same-type attack bonus: attacks sharing a type with the attacker hit harder.
+const STAB_MULTIPLIER: f64 = 1.5;
+
+/// Share of the damage a move deals to each target when it hits more than
+/// one, like Earthquake in a double battle.
+const SPREAD_MULTIPLIER: f64 = 0.75;
+
+/// The attacker tries to hit every target with the move it picked, unless
+/// its status condition keeps it from moving. Moves that only affect the
+/// attacker come without targets.
+fn take_turn(
+    (position, attacker): (Position, &mut BattlePokemon),
+    targets: &mut [(Position, &mut BattlePokemon)],
+    choice: Option<usize>,
+    rules: &BattleRules,
+    field: &Field,
+    rng: &mut dyn RngCore,
+    report: &mut RoundReport,
+) {
+    let status = attacker.get_status();
+    if !attacker.try_to_move(rng) {
+        if let Some(status) = status {
+            report
+                .immobilized
+                .push((attacker.get_name().to_string(), status));
+            report.log(BattleEvent::Immobilized {
+                fighter: Fighter::new(position, attacker.get_name()),
+                status,
+            });
+        }
+        return;
+    }
+    if status.is_some() && attacker.get_status().is_none() {
+        report.log(BattleEvent::StatusCured {
+            fighter: Fighter::new(position, attacker.get_name()),
+        });
+    }
+
+    let used_move = attacker.use_move(choice);
+    report.log(BattleEvent::MoveUsed {
+        fighter: Fighter::new(position, attacker.get_name()),
+        move_name: used_move.get_name().to_string(),
+        move_type: used_move.get_type().to_string(),
+    });
+    let spread = if targets.len() > 1 {
+        SPREAD_MULTIPLIER
+    } else {
+        1.0
+    };
+    let attacker_name = attacker.get_name().to_string();
+    let attack_on = |defender: &BattlePokemon| AttackReport {
+        attacker: attacker_name.clone(),
+        defender: defender.get_name().to_string(),
+        move_name: used_move.get_name().to_string(),
+        hit: false,
+        critical: false,
+        damage: 0,
+        inflicted: None,
+        stat_changes: Vec::new(),
+    };
+    let mut attacks = Vec::new();
+    if targets.is_empty() {
+        attacks.push(AttackReport {
+            hit: true,
+            ..attack_on(attacker)
+        });
+    }
+    // Damage dealt to all targets, if the move dealt any
+    let mut dealt = None;
+
+    for (target, defender) in targets.iter_mut() {
+        let (target, defender) = (*target, &mut **defender);
+        let mut attack = attack_on(defender);
+        if let Some(chance) = hit_chance(&used_move, attacker, defender)
+            && !rng.random_bool(chance)
+        {
+            report.log(BattleEvent::Miss {
+                fighter: Fighter::new(position, attacker.get_name()),
+                target: Fighter::new(target, defender.get_name()),
+            });
+            attacks.push(attack);
+            continue;
+        }
+        attack.hit = true;
+
+        if let Some(ability) = defender.get_ability()
+            && ability.is_immune(used_move.get_type())
+        {
+            report.ability_activated(target, defender, ability.name());
+            attacks.push(attack);
+            continue;
+        }
+
+        // Psychic terrain shields grounded Pokemon from priority moves
+        if field.blocks_move(&used_move, defender) {
+            attacks.push(attack);
+            continue;
+        }
+
+        if used_move.is_damaging() {
+            let roll = calculate_damage(attacker, defender, &used_move, spread, rules, field, rng);
+            let mut damage = roll.damage;
+            let attacker_status = attacker.get_status();
+            if let Some(ability) = defender.get_ability()
+                && ability.on_damage_taken(defender, attacker, &used_move, &mut damage, rng)
+            {
+                report.ability_activated(target, defender, ability.name());
+            }
+            // Static may paralyze the attacker on contact
+            if let Some(status) = attacker.get_status()
+                && attacker_status.is_none()
+            {
+                report.log(BattleEvent::StatusApplied {
+                    target: Fighter::new(position, attacker.get_name()),
+                    status,
+                });
+            }
+            trigger_item((target, defender), report, |item, owner| {
+                item.on_damage_taken(owner, &mut damage)
+            });
+            defender.take_damage(damage);
+            *dealt.get_or_insert(0) += damage;
+            attack.critical = roll.critical;
+            attack.damage = damage;
+            if roll.critical {
+                report.log(BattleEvent::CriticalHit {
+                    target: Fighter::new(target, defender.get_name()),
+                });
+            }
+            report.log(BattleEvent::Damage {
+                target: Fighter::new(target, defender.get_name()),
+                damage,
+                hp: defender.get_current_hp(),
+                effectiveness: type_effectiveness(used_move.get_type(), defender, |a, d| {
+                    rules.type_chart.get_effectiveness(a, d)
+                }),
+            });
+            report.log_faint(target, defender);
+
+            // Fire melts the ice
+            if used_move.get_type() == "fire"
+                && defender.get_status() == Some(StatusCondition::Freeze)
+            {
+                defender.cure_status();
+                report.log(BattleEvent::StatusCured {
+                    fighter: Fighter::new(target, defender.get_name()),
+                });
+            }
+        }
+
+        if let Some((status, chance)) = used_move.get_ailment()
+            && rng.random_bool(chance)
+            && !field.prevents_status(status, defender)
+            && defender.inflict_status(status, rng)
+        {
+            attack.inflicted = Some(status);
+            report.log(BattleEvent::StatusApplied {
+                target: Fighter::new(target, defender.get_name()),
+                status,
+            });
+        }
+
+        if !used_move.changes_own_stats()
+            && !used_move.get_stat_changes().is_empty()
+            && rng.random_bool(used_move.get_stat_chance())
+        {
+            change_stats((target, defender), &used_move, &mut attack, report);
         }
+        attacks.push(attack);
+    }
+
+    // Life Orb takes its toll once, however many targets were hit
+    if let Some(damage) = dealt {
+        trigger_item((position, attacker), report, |item, owner| {
+            item.after_attack(owner, damage)
+        });
+    }
+
+    // Struggle hurts the attacker too, so two Pokemon out of PP cannot heal
+    // through it forever
+    if used_move.is_struggle() && !attacker.is_fainted() {
+        attacker.take_damage((attacker.get_max_hp() / 4).max(1));
+        report.log_hp(position, attacker, used_move.get_name());
+    }
+
+    // The attacker's own stats change once, if the move connected
+    if used_move.changes_own_stats()
+        && !used_move.get_stat_changes().is_empty()
+        && let Some(attack) = attacks.iter_mut().rev().find(|attack| attack.hit)
+        && rng.random_bool(used_move.get_stat_chance())
+    {
+        change_stats((position, attacker), &used_move, attack, report);
+    }
+    report.attacks.extend(attacks);
+
+    // Berries react to the HP and status the move left behind
+    trigger_item((position, attacker), report, |item, owner| {
+        item.on_update(owner)
+    });
+    for (target, defender) in targets.iter_mut() {
+        trigger_item((*target, defender), report, |item, owner| {
+            item.on_update(owner)
+        });
+    }
+}
+
+/// Applies the stat changes of `used_move` to the Pokemon at `position`,
+/// recording the ones that were not blocked by the -6..+6 limits.
+fn change_stats(
+    (position, target): (Position, &mut BattlePokemon),
+    used_move: &Move,
+    attack: &mut AttackReport,
+    report: &mut RoundReport,
+) {
+    if target.is_fainted() {
+        return;
+    }
+    for stat_change in used_move.get_stat_changes() {
+        let stat = &stat_change.stat.name;
+        let change = target.get_stages_mut().change(stat, stat_change.change);
+        if change != 0 {
+            attack
+                .stat_changes
+                .push((target.get_name().to_string(), stat.clone(), change));
+            report.log(BattleEvent::StatChanged {
+                fighter: Fighter::new(position, target.get_name()),
+                stat: stat.clone(),
+                stages: change,
+            });
+        }
+    }
+}
+
+/// Probability of `used_move` hitting, or `None` if it cannot miss.
+///
+/// The move's accuracy is scaled by the attacker's accuracy stage minus the
+/// defender's evasion stage.
+fn hit_chance(used_move: &Move, attacker: &BattlePokemon, defender: &BattlePokemon) -> Option<f64> {
+    let accuracy = used_move.accuracy? as f64 / 100.0;
+    let stage = attacker.get_stages().accuracy - defender.get_stages().evasion;
+    Some((accuracy * accuracy_multiplier(stage)).clamp(0.0, 1.0))
+}
+
+/// Index of the move to use, or `None` when the attacker has to struggle.
+///
+/// Picks the damaging move with PP left that is expected to deal the most
+/// damage, or a priority move that is expected to finish the defender off
+/// before it can act. If the best attack cannot knock the defender out
+/// within two hits, a status move comes first while the defender can still
+/// get its condition, then a stat move until the stats it changes are two
+/// stages in its favor.
+///
+/// A Pokemon locked into a move by its choice item keeps using that move,
+/// and never sets up with status or stat moves.
+fn choose_move(
+    attacker: &BattlePokemon,
+    defender: &BattlePokemon,
+    rules: &BattleRules,
+    field: &Field,
+) -> Option<usize> {
+    let moves = attacker.get_moves();
+    if let Some(index) = attacker.get_locked_move() {
+        return moves.get(index).filter(|slot| slot.pp > 0).map(|_| index);
+    }
+    let expected = |m: &Move| expected_damage(attacker, defender, m, rules, field);
+    let best_attack = moves
+        .iter()
+        .enumerate()
+        .filter(|(_, slot)| slot.pp > 0 && slot.known_move.is_damaging())
+        .map(|(index, slot)| (index, expected(&slot.known_move)))
+        // min_by keeps the first of equally good moves
+        .min_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    if let Some(index) = moves.iter().position(|slot| {
+        slot.pp > 0
+            && slot.known_move.is_damaging()
+            && slot.known_move.get_priority() > 0
+            && expected(&slot.known_move) >= defender.get_current_hp() as f64
+    }) {
+        return Some(index);
+    }
+
+    let can_inflict = |m: &Move| {
+        m.is_status_inflicting()
+            && m.get_ailment().is_some_and(|(status, _)| {
+                defender.get_status().is_none()
+                    && !status.is_immune(&defender.get_types())
+                    && !field.prevents_status(status, defender)
+            })
+    };
+    let quick_knockout =
+        best_attack.is_some_and(|(_, damage)| damage * 2.0 >= defender.get_current_hp() as f64);
+    let sets_up = !quick_knockout && !attacker.get_item().is_some_and(|item| item.locks_move());
+    if sets_up
+        && let Some(index) = moves
+            .iter()
+            .position(|slot| slot.pp > 0 && can_inflict(&slot.known_move))
+    {
+        return Some(index);
+    }
 
-        // Both pokemon still alive, round complete
-        Some(())
+    let worth_changing = |m: &Move| {
+        m.is_stat_changing()
+            && m.get_stat_changes().iter().any(|c| {
+                if m.changes_own_stats() {
+                    c.change > 0 && attacker.get_stages().get(&c.stat.name) < 2
+                } else {
+                    c.change < 0 && defender.get_stages().get(&c.stat.name) > -2
+                }
+            })
+    };
+    if sets_up
+        && let Some(index) = moves
+            .iter()
+            .position(|slot| slot.pp > 0 && worth_changing(&slot.known_move))
+    {
+        return Some(index);
     }
+    best_attack.map(|(index, _)| index)
 }
 
-// Helper function to calculate damage
+/// Damage `used_move` is expected to deal, weighed by its chance to hit.
+fn expected_damage(
+    attacker: &BattlePokemon,
+    defender: &BattlePokemon,
+    used_move: &Move,
+    rules: &BattleRules,
+    field: &Field,
+) -> f64 {
+    let multiplier = damage_multiplier(used_move, attacker, defender, rules, field);
+    rules
+        .damage_model
+        .estimate(attacker, defender, used_move, multiplier)
+        * hit_chance(used_move, attacker, defender).unwrap_or(1.0)
+}
+
+// Helper function to calculate damage, scaled by `spread` for moves that
+// hit more than one target
 fn calculate_damage(
+    attacker: &BattlePokemon,
+    defender: &BattlePokemon,
+    used_move: &Move,
+    spread: f64,
+    rules: &BattleRules,
+    field: &Field,
+    rng: &mut dyn RngCore,
+) -> DamageRoll {
+    let multiplier = damage_multiplier(used_move, attacker, defender, rules, field) * spread;
+    rules
+        .damage_model
+        .calculate(attacker, defender, used_move, multiplier, rng)
+}
+
+/// Type multiplier of `used_move`, boosted by the attacker's held item and
+/// by weather and terrain.
+fn damage_multiplier(
+    used_move: &Move,
+    attacker: &BattlePokemon,
+    defender: &BattlePokemon,
+    rules: &BattleRules,
+    field: &Field,
+) -> f64 {
+    let type_multiplier = get_type_multiplier(used_move.get_type(), attacker, defender, |a, d| {
+        rules.type_chart.get_effectiveness(a, d)
+    });
+    type_multiplier
+        * attacker.get_item_damage_modifier(used_move)
+        * field.damage_modifier(used_move, attacker, defender)
+}
+
+/// Combined effectiveness against every defender type, times STAB if it
+/// applies. Zero if the defender's ability makes it immune.
+fn get_type_multiplier(
+    attack_type: &str,
     attacker: &BattlePokemon,
     defender: &BattlePokemon,
     effectiveness: impl Fn(&str, &str) -> f64,
-) -> i64 {
-    let base_damage = attacker.get_attack() / defender.get_defense() * 20;
-    let multiplier = effectiveness(attacker.get_type(), defender.get_type());
-    let damage = (base_damage as f64 * multiplier) as i64;
+) -> f64 {
+    if defender.is_immune_by_ability(attack_type) {
+        return 0.0;
+    }
+    let type_effectiveness = type_effectiveness(attack_type, defender, effectiveness);
+    let stab = if attacker.get_types().contains(&attack_type) {
+        STAB_MULTIPLIER
+    } else {
+        1.0
+    };
+    type_effectiveness * stab
+}
+
+/// How effective `attack_type` is against the types of `defender`, e.g. 4.0
+/// for an electric move against a water/flying Pokemon.
+fn type_effectiveness(
+    attack_type: &str,
+    defender: &BattlePokemon,
+    effectiveness: impl Fn(&str, &str) -> f64,
+) -> f64 {
+    defender
+        .get_types()
+        .iter()
+        .map(|defender_type| effectiveness(attack_type, defender_type))
+        .product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::damage::MainSeriesDamage;
+    use crate::models::moves::DamageClass;
+    use crate::models::pokemon::{AbilityInfo, Pokemon, PokemonAbility, Type, TypeInfo};
+    use crate::models::status::SLEEP_TURNS;
+    use crate::models::test_utils::{create_test_move, create_test_pokemon};
+    use crate::models::type_chart::TypeChart;
+    use strategy::TypeAware;
+
+    const ATTACKER: Position = Position { side: 0, slot: 0 };
+    const DEFENDER: Position = Position { side: 1, slot: 0 };
+    const PARTNER: Position = Position { side: 1, slot: 1 };
+
+    fn add_type(pokemon: &mut Pokemon, type_name: &str) {
+        pokemon.types.push(Type {
+            type_info: TypeInfo {
+                name: type_name.to_string(),
+            },
+        });
+    }
+
+    #[test]
+    fn test_dual_type_effectiveness_multiplies() {
+        let chart = TypeChart::standard();
+        let geodude = create_test_pokemon("geodude", 40, 80, 100, 20, "rock");
+        let mut charizard = create_test_pokemon("charizard", 78, 84, 78, 100, "fire");
+        add_type(&mut charizard, "flying");
+        let mut gyarados = create_test_pokemon("gyarados", 95, 125, 79, 81, "water");
+        add_type(&mut gyarados, "flying");
+
+        let attacker = BattlePokemon::new(&geodude);
+        let effectiveness = |a: &str, d: &str| chart.get_effectiveness(a, d);
+
+        let vs_charizard = BattlePokemon::new(&charizard);
+        assert_eq!(
+            get_type_multiplier("rock", &attacker, &vs_charizard, effectiveness),
+            4.0 * STAB_MULTIPLIER
+        );
+        let vs_gyarados = BattlePokemon::new(&gyarados);
+        assert_eq!(
+            get_type_multiplier("ground", &attacker, &vs_gyarados, effectiveness),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_choose_move_prefers_effective_moves_with_pp() {
+        let rules = BattleRules::default();
+        let pikachu = create_test_pokemon("pikachu", 35, 55, 40, 90, "electric");
+        let mut gyarados = create_test_pokemon("gyarados", 95, 125, 79, 81, "water");
+        add_type(&mut gyarados, "flying");
+        let defender = BattlePokemon::new(&gyarados);
+        let mut attacker = BattlePokemon::new(&pikachu).with_moves(vec![
+            create_test_move("body-slam", 85, "normal", DamageClass::Physical),
+            create_test_move("thunder-shock", 40, "electric", DamageClass::Special),
+        ]);
+
+        // 40 * 4 (super effective twice) * 1.5 (STAB) beats 85
+        assert_eq!(
+            choose_move(&attacker, &defender, &rules, &Field::default()),
+            Some(1)
+        );
+
+        for _ in 0..10 {
+            attacker.use_move(Some(1));
+        }
+        assert_eq!(
+            choose_move(&attacker, &defender, &rules, &Field::default()),
+            Some(0)
+        );
+        for _ in 0..10 {
+            attacker.use_move(Some(0));
+        }
+        assert_eq!(
+            choose_move(&attacker, &defender, &rules, &Field::default()),
+            None
+        );
+        assert_eq!(attacker.use_move(None).get_name(), "struggle");
+    }
+
+    #[test]
+    fn test_accuracy_and_evasion() {
+        let rules = BattleRules::default();
+        let mut rng = StdRng::seed_from_u64(0);
+        let pikachu = create_test_pokemon("pikachu", 35, 55, 40, 90, "electric");
+        let eevee = create_test_pokemon("eevee", 55, 55, 50, 55, "normal");
+        let mut thunder = create_test_move("thunder", 110, "electric", DamageClass::Special);
+        thunder.accuracy = Some(70);
+        let mut attacker = BattlePokemon::new(&pikachu).with_moves(vec![thunder.clone()]);
+        let mut defender = BattlePokemon::new(&eevee);
+
+        assert_eq!(hit_chance(&thunder, &attacker, &defender), Some(0.7));
+        assert_eq!(hit_chance(&Move::struggle(), &attacker, &defender), None);
+        defender.get_stages_mut().evasion = 1;
+        attacker.get_stages_mut().accuracy = 1;
+        assert_eq!(hit_chance(&thunder, &attacker, &defender), Some(0.7));
+        // Accuracy +1 against evasion +6 is stage -5, i.e. 3/8
+        defender.get_stages_mut().evasion = 6;
+        assert_eq!(
+            hit_chance(&thunder, &attacker, &defender),
+            Some(0.7 * 0.375)
+        );
+
+        let mut never_hits = thunder.clone();
+        never_hits.accuracy = Some(0);
+        let mut attacker = BattlePokemon::new(&pikachu).with_moves(vec![never_hits]);
+        let mut report = RoundReport::default();
+        let choice = choose_move(&attacker, &defender, &rules, &Field::default());
+        take_turn(
+            (ATTACKER, &mut attacker),
+            &mut [(DEFENDER, &mut defender)],
+            choice,
+            &rules,
+            &Field::default(),
+            &mut rng,
+            &mut report,
+        );
+        assert_eq!(report.misses(), 1);
+        assert_eq!(report.attacks[0].damage, 0);
+        assert_eq!(defender.get_current_hp(), defender.get_max_hp());
+    }
+
+    #[test]
+    fn test_stab_only_for_matching_types() {
+        let chart = TypeChart::standard();
+        let pikachu = create_test_pokemon("pikachu", 35, 55, 40, 90, "electric");
+        let eevee = create_test_pokemon("eevee", 55, 55, 50, 55, "normal");
+        let attacker = BattlePokemon::new(&pikachu);
+        let defender = BattlePokemon::new(&eevee);
+        let effectiveness = |a: &str, d: &str| chart.get_effectiveness(a, d);
+
+        assert_eq!(
+            get_type_multiplier("electric", &attacker, &defender, effectiveness),
+            STAB_MULTIPLIER
+        );
+        assert_eq!(
+            get_type_multiplier("normal", &attacker, &defender, effectiveness),
+            1.0
+        );
+    }
+
+    /// No luck and a single move without side effects, so only the status
+    /// condition under test can change the result.
+    fn plain_rules() -> BattleRules {
+        BattleRules {
+            damage_model: Box::new(MainSeriesDamage {
+                critical_chance: 0.0,
+                random_spread: false,
+                ..MainSeriesDamage::default()
+            }),
+            movedex: Movedex::from_json(
+                r#"[{ "name": "headbutt", "power": 70, "accuracy": 100, "pp": 15,
+                      "type": { "name": "normal" }, "damage_class": { "name": "physical" } }]"#,
+            )
+            .unwrap(),
+            ..BattleRules::default()
+        }
+    }
+
+    /// Mirror match in which Ash is a bit faster and wins unless his Pokemon
+    /// starts with `status`.
+    fn mirror_match_winner(status: Option<StatusCondition>) -> (String, Vec<RoundReport>) {
+        let rules = plain_rules();
+        let ash = Trainer::new(
+            "Ash".to_string(),
+            create_test_pokemon("snorlax", 100, 50, 100, 51, "normal"),
+        );
+        let gary = Trainer::new(
+            "Gary".to_string(),
+            create_test_pokemon("snorlax", 100, 50, 100, 50, "normal"),
+        );
+
+        let mut battle = Battle::new(&ash, &gary, &rules, 1).begin();
+        if let Some(status) = status {
+            let mut rng = StdRng::seed_from_u64(7);
+            assert!(battle.participants.sides[0]
+                .active_mut(0)
+                .inflict_status(status, &mut rng));
+        }
+        let mut reports = Vec::new();
+        let mut rounds = battle.rounds();
+        while let Some(report) = rounds.execute_round(&rules) {
+            reports.push(report);
+        }
+        (battle.finish().winner().get_name().to_string(), reports)
+    }
+
+    #[test]
+    fn test_round_limit_ends_battle_on_remaining_hp() {
+        let rules = BattleRules {
+            max_rounds: 2,
+            ..plain_rules()
+        };
+        let ash = Trainer::new(
+            "Ash".to_string(),
+            create_test_pokemon("snorlax", 160, 50, 150, 30, "normal"),
+        );
+        let gary = Trainer::new(
+            "Gary".to_string(),
+            create_test_pokemon("snorlax", 160, 50, 100, 30, "normal"),
+        );
+
+        let mut battle = Battle::new(&ash, &gary, &rules, 1).begin();
+        let mut rounds = battle.rounds();
+        let mut played = 0;
+        while rounds.execute_round(&rules).is_some() {
+            played += 1;
+        }
+        let battle = battle.finish();
+
+        // Nobody fainted, but Ash's bulkier Snorlax has more HP left
+        assert_eq!(played, 2);
+        assert!(battle.timed_out());
+        assert_eq!(battle.winner().get_name(), "Ash");
+        assert_eq!(battle.looser().get_name(), "Gary");
+    }
+
+    #[test]
+    fn test_seed_decides_when_both_sides_faint_together() {
+        let rules = BattleRules {
+            arena: Arena::default().with_weather(Some(Weather::Sandstorm)),
+            ..plain_rules()
+        };
+        let ash = Trainer::new(
+            "Ash".to_string(),
+            create_test_pokemon("snorlax", 100, 50, 100, 51, "normal"),
+        );
+        let gary = Trainer::new(
+            "Gary".to_string(),
+            create_test_pokemon("snorlax", 100, 50, 100, 50, "normal"),
+        );
+
+        let mut winners = std::collections::HashSet::new();
+        for seed in 0..16 {
+            // Ash knocks out Gary, then the sandstorm knocks out Ash
+            let mut battle = Battle::new(&ash, &gary, &rules, seed).begin();
+            for side in &mut battle.participants.sides {
+                let pokemon = side.active_mut(0);
+                pokemon.take_damage(pokemon.get_max_hp() - 1);
+            }
+            let mut rounds = battle.rounds();
+            while rounds.execute_round(&rules).is_some() {}
+
+            assert!(battle.participants.sides.iter().all(|s| s.is_defeated()));
+            winners.insert(battle.finish().winner().get_name().to_string());
+        }
+        assert_eq!(winners.len(), 2, "one side always won: {winners:?}");
+    }
+
+    #[test]
+    fn test_struggle_hurts_the_attacker() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let rules = plain_rules();
+        let chansey = create_test_pokemon("chansey", 250, 5, 5, 50, "normal");
+        let mut attacker = BattlePokemon::new(&chansey);
+        let mut defender = BattlePokemon::new(&chansey);
+        let mut report = RoundReport::default();
+
+        take_turn(
+            (ATTACKER, &mut attacker),
+            &mut [(DEFENDER, &mut defender)],
+            None,
+            &rules,
+            &Field::default(),
+            &mut rng,
+            &mut report,
+        );
+        assert_eq!(
+            attacker.get_current_hp(),
+            attacker.get_max_hp() - attacker.get_max_hp() / 4
+        );
+        assert_eq!(
+            report.events.last(),
+            Some(&BattleEvent::HpChanged {
+                fighter: Fighter::new(ATTACKER, "chansey"),
+                hp: attacker.get_current_hp(),
+                cause: "struggle".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_status_conditions_decide_mirror_match() {
+        assert_eq!(mirror_match_winner(None).0, "Ash");
+
+        for status in [
+            StatusCondition::Burn,
+            StatusCondition::Poison,
+            StatusCondition::Paralysis,
+            StatusCondition::Sleep,
+            StatusCondition::Freeze,
+        ] {
+            let (winner, reports) = mirror_match_winner(Some(status));
+            assert_eq!(winner, "Gary", "{status} should cost Ash the battle");
+
+            let chipped = reports.iter().any(|r| !r.residual_damage.is_empty());
+            let immobilized = reports.iter().any(|r| !r.immobilized.is_empty());
+            match status {
+                StatusCondition::Burn | StatusCondition::Poison => assert!(chipped),
+                _ => assert!(immobilized),
+            }
+        }
+    }
+
+    #[test]
+    fn test_status_stat_modifiers_and_residual_damage() {
+        let mut rng = StdRng::seed_from_u64(0);
+        // 155 HP, 120 attack and 120 speed at level 50
+        let mon = create_test_pokemon("mon", 80, 100, 50, 100, "normal");
+
+        let mut burned = BattlePokemon::new(&mon);
+        burned.inflict_status(StatusCondition::Burn, &mut rng);
+        assert_eq!(burned.get_attack(), 60);
+        assert_eq!(burned.take_residual_damage(), 9);
+
+        let mut poisoned = BattlePokemon::new(&mon);
+        poisoned.inflict_status(StatusCondition::Poison, &mut rng);
+        assert_eq!(poisoned.take_residual_damage(), 19);
+        assert_eq!(poisoned.get_current_hp(), 136);
+
+        let mut paralyzed = BattlePokemon::new(&mon);
+        paralyzed.inflict_status(StatusCondition::Paralysis, &mut rng);
+        assert_eq!(paralyzed.get_speed(), 60);
+        assert_eq!(paralyzed.take_residual_damage(), 0);
+    }
+
+    #[test]
+    fn test_sleep_wears_off_and_freeze_thaws() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mon = create_test_pokemon("mon", 80, 100, 50, 100, "normal");
+
+        let mut sleeper = BattlePokemon::new(&mon);
+        sleeper.inflict_status(StatusCondition::Sleep, &mut rng);
+        let skipped = (0..10)
+            .take_while(|_| !sleeper.try_to_move(&mut rng))
+            .count();
+        assert!(
+            SLEEP_TURNS.contains(&(skipped as u8)),
+            "slept {skipped} turns"
+        );
+        assert_eq!(sleeper.get_status(), None);
+
+        let mut frozen = BattlePokemon::new(&mon);
+        frozen.inflict_status(StatusCondition::Freeze, &mut rng);
+        while !frozen.try_to_move(&mut rng) {}
+        assert_eq!(frozen.get_status(), None);
+    }
+
+    #[test]
+    fn test_one_status_at_a_time_and_type_immunity() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let pikachu = create_test_pokemon("pikachu", 35, 55, 40, 90, "electric");
+        let mut pokemon = BattlePokemon::new(&pikachu);
+
+        assert!(!pokemon.inflict_status(StatusCondition::Paralysis, &mut rng));
+        assert!(pokemon.inflict_status(StatusCondition::Burn, &mut rng));
+        assert!(!pokemon.inflict_status(StatusCondition::Poison, &mut rng));
+        assert_eq!(pokemon.get_status(), Some(StatusCondition::Burn));
+    }
 
-    // Ensure minimum damage of 1 to prevent infinite battles
-    damage.max(1)
+    #[test]
+    fn test_stat_moves_change_stages() {
+        let rules = BattleRules::default();
+        let mut rng = StdRng::seed_from_u64(0);
+        let mon = create_test_pokemon("mon", 80, 100, 50, 100, "normal");
+        let swords_dance = rules.movedex.get("swords-dance").unwrap().clone();
+        let growl = rules.movedex.get("growl").unwrap().clone();
+        let mut attacker = BattlePokemon::new(&mon).with_moves(vec![swords_dance, growl]);
+        let mut defender = BattlePokemon::new(&mon);
+
+        // Nothing can knock the defender out yet, so the attacker sets up
+        assert_eq!(
+            choose_move(&attacker, &defender, &rules, &Field::default()),
+            Some(0)
+        );
+        let mut report = RoundReport::default();
+        let choice = choose_move(&attacker, &defender, &rules, &Field::default());
+        take_turn(
+            (ATTACKER, &mut attacker),
+            &mut [],
+            choice,
+            &rules,
+            &Field::default(),
+            &mut rng,
+            &mut report,
+        );
+        assert_eq!(
+            report.attacks[0].stat_changes,
+            [("mon".to_string(), "attack".to_string(), 2)]
+        );
+        assert_eq!(attacker.get_attack(), 240);
+
+        // At +2 the attacker moves on to lowering the defender's attack
+        assert_eq!(
+            choose_move(&attacker, &defender, &rules, &Field::default()),
+            Some(1)
+        );
+        let choice = choose_move(&attacker, &defender, &rules, &Field::default());
+        take_turn(
+            (ATTACKER, &mut attacker),
+            &mut [(DEFENDER, &mut defender)],
+            choice,
+            &rules,
+            &Field::default(),
+            &mut rng,
+            &mut report,
+        );
+        assert_eq!(defender.get_stages().attack, -1);
+        assert_eq!(defender.get_attack(), 80);
+    }
+
+    #[test]
+    fn test_turn_order_uses_priority_then_speed() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let rattata = create_test_pokemon("rattata", 30, 56, 35, 72, "normal");
+        let pikachu = create_test_pokemon("pikachu", 35, 55, 40, 90, "electric");
+        let mut quick_attack =
+            create_test_move("quick-attack", 40, "normal", DamageClass::Physical);
+        quick_attack.priority = 1;
+        let mut slow = BattlePokemon::new(&rattata).with_moves(vec![quick_attack]);
+        let fast = BattlePokemon::new(&pikachu).with_moves(vec![create_test_move(
+            "thunder-shock",
+            40,
+            "electric",
+            DamageClass::Special,
+        )]);
+
+        let fast_speed = turn_speed(&fast, Some(0));
+        assert_eq!(
+            turn_order(&[turn_speed(&slow, Some(0)), fast_speed], &mut rng),
+            [0, 1]
+        );
+        // Struggle has no priority
+        assert_eq!(
+            turn_order(&[turn_speed(&slow, None), fast_speed], &mut rng),
+            [1, 0]
+        );
+        // 72 at +1 is 108
+        slow.get_stages_mut().speed = 1;
+        assert_eq!(
+            turn_order(&[turn_speed(&slow, None), fast_speed], &mut rng),
+            [0, 1]
+        );
+
+        // Four combatants: priority first, then speed, ties in random order
+        let four = [(0, 90), (1, 30), (0, 120), (0, 90)];
+        let mut firsts = 0;
+        for _ in 0..100 {
+            let order = turn_order(&four, &mut rng);
+            assert_eq!(order[..2], [1, 2]);
+            if order[2] == 0 {
+                firsts += 1;
+            }
+        }
+        assert!((30..70).contains(&firsts), "won {firsts} of 100 speed ties");
+    }
+
+    fn add_ability(pokemon: &mut Pokemon, name: &str) {
+        pokemon.abilities.push(PokemonAbility {
+            ability: AbilityInfo {
+                name: name.to_string(),
+            },
+            is_hidden: false,
+            slot: 1,
+        });
+    }
+
+    #[test]
+    fn test_abilities_hook_into_the_battle() {
+        let rules = plain_rules();
+        let mut gyarados = create_test_pokemon("gyarados", 95, 125, 79, 81, "water");
+        add_ability(&mut gyarados, "intimidate");
+        let mut gengar = create_test_pokemon("gengar", 60, 65, 60, 110, "ghost");
+        add_ability(&mut gengar, "levitate");
+        let ash = Trainer::new("Ash".to_string(), gyarados);
+        let gary = Trainer::new("Gary".to_string(), gengar);
+
+        let mut battle = Battle::new(&ash, &gary, &rules, 1).begin();
+        let report = battle.rounds().execute_round(&rules).unwrap();
+        assert_eq!(
+            report.abilities[0],
+            ("gyarados".to_string(), "intimidate".to_string())
+        );
+        assert_eq!(
+            battle.participants.sides[1].active(0).get_stages().attack,
+            -1
+        );
+        let report = battle.rounds().execute_round(&rules).unwrap();
+        assert!(report.abilities.is_empty(), "intimidate only on switch-in");
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let earthquake = create_test_move("earthquake", 100, "ground", DamageClass::Physical);
+        let diglett = create_test_pokemon("diglett", 10, 55, 25, 95, "ground");
+        let mut attacker = BattlePokemon::new(&diglett).with_moves(vec![earthquake]);
+        let mut defender = gary.get_pokemon();
+        let mut report = RoundReport::default();
+        take_turn(
+            (ATTACKER, &mut attacker),
+            &mut [(DEFENDER, &mut defender)],
+            Some(0),
+            &rules,
+            &Field::default(),
+            &mut rng,
+            &mut report,
+        );
+        assert_eq!(report.attacks[0].damage, 0);
+        assert_eq!(
+            report.abilities,
+            [("gengar".to_string(), "levitate".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_held_items_hook_into_the_battle() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let rules = plain_rules();
+        let machamp = create_test_pokemon("machamp", 90, 130, 80, 55, "fighting");
+        let magikarp = create_test_pokemon("magikarp", 20, 10, 55, 80, "water");
+        let moves = vec![
+            create_test_move("karate-chop", 50, "fighting", DamageClass::Physical),
+            create_test_move("double-edge", 120, "normal", DamageClass::Physical),
+        ];
+
+        // Choice Band boosts attack but locks Machamp into its first move
+        let band = items::lookup("choice-band").unwrap();
+        let mut attacker = BattlePokemon::new(&machamp)
+            .with_moves(moves)
+            .with_item(Some(band));
+        assert_eq!(attacker.get_attack(), 225);
+        let mut defender = BattlePokemon::new(&magikarp);
+        assert_eq!(
+            choose_move(&attacker, &defender, &rules, &Field::default()),
+            Some(1)
+        );
+        attacker.use_move(Some(0));
+        assert_eq!(attacker.get_locked_move(), Some(0));
+        assert_eq!(
+            choose_move(&attacker, &defender, &rules, &Field::default()),
+            Some(0)
+        );
+
+        // Focus Sash holds on once from full HP and is gone afterwards
+        let sash = items::lookup("focus-sash").unwrap();
+        defender = defender.with_item(Some(sash));
+        let mut report = RoundReport::default();
+        take_turn(
+            (ATTACKER, &mut attacker),
+            &mut [(DEFENDER, &mut defender)],
+            Some(0),
+            &rules,
+            &Field::default(),
+            &mut rng,
+            &mut report,
+        );
+        assert_eq!(defender.get_current_hp(), 1);
+        assert_eq!(
+            report.items,
+            [("magikarp".to_string(), "focus-sash".to_string())]
+        );
+        assert!(defender.get_item().is_none());
+        take_turn(
+            (ATTACKER, &mut attacker),
+            &mut [(DEFENDER, &mut defender)],
+            Some(0),
+            &rules,
+            &Field::default(),
+            &mut rng,
+            &mut report,
+        );
+        assert!(defender.is_fainted());
+    }
+
+    #[test]
+    fn test_weather_chips_away_until_it_runs_out() {
+        let rules = BattleRules {
+            arena: Arena::default()
+                .with_weather(Some(Weather::Sandstorm))
+                .with_turns(Some(1)),
+            ..plain_rules()
+        };
+        let ash = Trainer::new(
+            "Ash".to_string(),
+            create_test_pokemon("snorlax", 100, 50, 100, 51, "normal"),
+        );
+        let brock = Trainer::new(
+            "Brock".to_string(),
+            create_test_pokemon("geodude", 40, 80, 100, 20, "rock"),
+        );
+
+        let mut battle = Battle::new(&ash, &brock, &rules, 1).begin();
+        let report = battle.rounds().execute_round(&rules).unwrap();
+        // Snorlax has 175 HP, rock types are immune
+        assert_eq!(
+            report.weather_damage,
+            [("snorlax".to_string(), Weather::Sandstorm, 10)]
+        );
+        assert_eq!(report.weather_ended, Some(Weather::Sandstorm));
+        assert_eq!(battle.get_field().get_weather(), None);
+
+        let report = battle.rounds().execute_round(&rules).unwrap();
+        assert!(report.weather_damage.is_empty());
+    }
+
+    #[test]
+    fn test_battles_go_on_until_a_whole_team_fainted() {
+        let rules = plain_rules();
+        let ash = Trainer::with_team(
+            "Ash".to_string(),
+            vec![
+                create_test_pokemon("magikarp", 20, 10, 55, 80, "water"),
+                create_test_pokemon("snorlax", 160, 110, 65, 30, "normal"),
+            ],
+        )
+        .unwrap();
+        let gary = Trainer::new(
+            "Gary".to_string(),
+            create_test_pokemon("pikachu", 35, 55, 40, 90, "electric"),
+        );
+
+        let mut battle = Battle::new(&ash, &gary, &rules, 1).begin();
+        let mut reports = Vec::new();
+        let mut rounds = battle.rounds();
+        while let Some(report) = rounds.execute_round(&rules) {
+            reports.push(report);
+        }
+        let battle = battle.finish();
+
+        assert_eq!(battle.winner().get_name(), "Ash");
+        assert_eq!(
+            battle.fielded(),
+            (
+                vec!["magikarp".to_string(), "snorlax".to_string()],
+                vec!["pikachu".to_string()]
+            )
+        );
+        assert!(reports.iter().any(|r| r
+            .switches
+            .contains(&("Ash".to_string(), "snorlax".to_string()))));
+    }
+
+    #[test]
+    fn test_double_battles_with_spread_moves() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let rules = BattleRules {
+            format: BattleFormat::Doubles,
+            ..plain_rules()
+        };
+        let earthquake = Movedex::standard().get("earthquake").unwrap().clone();
+        let golem = create_test_pokemon("golem", 80, 120, 130, 45, "rock");
+        let snorlax = create_test_pokemon("snorlax", 160, 110, 65, 30, "normal");
+        let mut attacker = BattlePokemon::new(&golem).with_moves(vec![earthquake]);
+        let mut targets: Vec<BattlePokemon> =
+            (0..3).map(|_| BattlePokemon::new(&snorlax)).collect();
+
+        // Earthquake hits each of two targets for three quarters of the damage
+        let mut report = RoundReport::default();
+        let [one, two, three] = &mut targets[..] else {
+            unreachable!()
+        };
+        for defenders in [
+            &mut [(DEFENDER, one)][..],
+            &mut [(DEFENDER, two), (PARTNER, three)][..],
+        ] {
+            take_turn(
+                (ATTACKER, &mut attacker),
+                defenders,
+                Some(0),
+                &rules,
+                &Field::default(),
+                &mut rng,
+                &mut report,
+            );
+        }
+        let damage: Vec<i64> = report.attacks.iter().map(|a| a.damage).collect();
+        assert_eq!(damage[1], damage[2]);
+        assert!((damage[0] * 3 / 4 - damage[1]).abs() <= 1, "{damage:?}");
+
+        // Both trainers field two Pokemon and all four of them act
+        let ash =
+            Trainer::with_team("Ash".to_string(), vec![golem.clone(), snorlax.clone()]).unwrap();
+        let gary = Trainer::with_team(
+            "Gary".to_string(),
+            vec![
+                create_test_pokemon("pikachu", 35, 55, 40, 90, "electric"),
+                create_test_pokemon("charmander", 39, 52, 43, 65, "fire"),
+                create_test_pokemon("squirtle", 44, 48, 65, 43, "water"),
+            ],
+        )
+        .unwrap();
+        let mut battle = Battle::new(&ash, &gary, &rules, 1).begin();
+        let report = battle.rounds().execute_round(&rules).unwrap();
+        let attackers: Vec<&str> = report.attacks.iter().map(|a| a.attacker.as_str()).collect();
+        // Fastest first
+        assert_eq!(attackers, ["pikachu", "charmander", "golem", "snorlax"]);
+        assert!(report
+            .attacks
+            .iter()
+            .all(|a| (a.attacker == "golem" || a.attacker == "snorlax")
+                != (a.defender == "golem" || a.defender == "snorlax")));
+
+        let mut rounds = battle.rounds();
+        while rounds.execute_round(&rules).is_some() {}
+        let battle = battle.finish();
+        assert_eq!(battle.winner().get_name(), "Ash");
+        assert_eq!(battle.fielded().1.len(), 3);
+    }
+
+    #[test]
+    fn test_trainers_battle_with_their_own_strategy() {
+        let rules = BattleRules::default();
+        let team = vec![
+            create_test_pokemon("charmander", 39, 52, 43, 65, "fire"),
+            create_test_pokemon("bulbasaur", 45, 49, 49, 45, "grass"),
+            create_test_pokemon("squirtle", 44, 48, 65, 43, "water"),
+        ];
+        let smart = Trainer::with_team("Ash".to_string(), team.clone())
+            .unwrap()
+            .with_strategy(strategy::parse("lookahead").unwrap());
+        let random = Trainer::with_team("Gary".to_string(), team)
+            .unwrap()
+            .with_strategy(strategy::parse("random").unwrap());
+
+        let wins = (0..20)
+            .filter(|&seed| {
+                let mut battle = Battle::new(&smart, &random, &rules, seed).begin();
+                let mut rounds = battle.rounds();
+                while rounds.execute_round(&rules).is_some() {}
+                battle.finish().winner().get_name() == "Ash"
+            })
+            .count();
+        assert!(wins >= 15, "won {wins} of 20");
+    }
+
+    #[test]
+    fn test_switching_out_of_a_losing_matchup() {
+        let rules = BattleRules::default();
+        let field = Field::default();
+        let physical = |name, power, type_name| {
+            create_test_move(name, power, type_name, DamageClass::Physical)
+        };
+        let charmander = create_test_pokemon("charmander", 39, 52, 43, 65, "fire");
+        let bulbasaur = create_test_pokemon("bulbasaur", 45, 49, 49, 45, "grass");
+        let squirtle = create_test_pokemon("squirtle", 44, 48, 65, 43, "water");
+        let ash = Trainer::new("Ash".to_string(), charmander.clone());
+        let misty = Trainer::new("Misty".to_string(), squirtle.clone());
+        let participant = BattleParticipant {
+            team: vec![
+                BattlePokemon::new(&charmander).with_moves(vec![physical("ember", 40, "fire")]),
+                BattlePokemon::new(&bulbasaur).with_moves(vec![physical(
+                    "razor-leaf",
+                    55,
+                    "grass",
+                )]),
+            ],
+            active: vec![0],
+            fielded: vec![0],
+            settled: vec![false],
+            trainer: &ash,
+            strategy: &TypeAware,
+        };
+        let opponent = BattleParticipant {
+            team: vec![BattlePokemon::new(&squirtle).with_moves(vec![physical(
+                "waterfall",
+                80,
+                "water",
+            )])],
+            active: vec![0],
+            fielded: vec![0],
+            settled: vec![false],
+            trainer: &misty,
+            strategy: &TypeAware,
+        };
+        let mut participants = BattleParticipants {
+            sides: [participant, opponent],
+        };
+        let position = Position { side: 0, slot: 0 };
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut choose = |participants: &BattleParticipants, taken: &[usize]| {
+            let turn = Turn::new(participants, position, taken, &rules, &field);
+            TypeAware.choose_action(&turn, &mut rng)
+        };
+
+        // A Pokemon that just came in stays for at least one turn
+        assert_eq!(
+            choose(&participants, &[]),
+            Action::Move {
+                choice: Some(0),
+                target: Position { side: 1, slot: 0 }
+            }
+        );
+        participants.sides[0].settled[0] = true;
+        assert_eq!(choose(&participants, &[]), Action::Switch(1));
+        // Unless its partner already picked that team member
+        assert_ne!(choose(&participants, &[1]), Action::Switch(1));
+
+        let participant = &mut participants.sides[0];
+        let mut report = RoundReport::default();
+        participant.active_mut(0).get_stages_mut().attack = 2;
+        participant.switch_to(0, 1, &mut report);
+        assert_eq!(participant.active(0).get_name(), "bulbasaur");
+        assert_eq!(participant.team[0].get_stages().attack, 0);
+        assert_eq!(
+            report.switches,
+            [("Ash".to_string(), "bulbasaur".to_string())]
+        );
+    }
 }
@@ -0,0 +1,287 @@
+use std::fmt::Debug;
+
+use rand::{Rng, RngCore};
+
+use crate::models::moves::{DamageClass, Move};
+use crate::models::pokemon::BattlePokemon;
+use crate::models::status::StatusCondition;
+
+/// Chance that Static paralyzes a Pokemon touching its owner.
+pub const STATIC_CHANCE: f64 = 0.3;
+
+/// An ability hooks into the battle at fixed points. Every hook does nothing
+/// by default, so an ability only implements the ones it needs.
+///
+/// Hooks that change the battle return whether they did, so the activation
+/// shows up in the round report.
+pub trait Ability: Debug + Send + Sync {
+    /// PokeAPI name, e.g. `levitate`.
+    fn name(&self) -> &'static str;
+
+    /// When the owner enters the battle.
+    fn on_switch_in(&self, _owner: &mut BattlePokemon, _opponent: &mut BattlePokemon) -> bool {
+        false
+    }
+
+    /// Before the owner takes `damage`; can change it or strike back.
+    fn on_damage_taken(
+        &self,
+        _owner: &BattlePokemon,
+        _attacker: &mut BattlePokemon,
+        _used_move: &Move,
+        _damage: &mut i64,
+        _rng: &mut dyn RngCore,
+    ) -> bool {
+        false
+    }
+
+    /// Whether moves of `attack_type` cannot affect the owner.
+    fn is_immune(&self, _attack_type: &str) -> bool {
+        false
+    }
+
+    /// Multiplier of the owner's `stat` while `used_move` is in play.
+    fn stat_modifier(&self, _owner: &BattlePokemon, _stat: &str, _used_move: &Move) -> f64 {
+        1.0
+    }
+
+    /// At the end of every round the owner is still standing.
+    fn on_end_of_turn(&self, _owner: &mut BattlePokemon) -> bool {
+        false
+    }
+}
+
+/// Looks up an ability the battle engine implements by its PokeAPI name.
+pub fn lookup(name: &str) -> Option<&'static dyn Ability> {
+    let ability: &'static dyn Ability = match name {
+        "levitate" => &Levitate,
+        "intimidate" => &Intimidate,
+        "blaze" => &Pinch {
+            name: "blaze",
+            boosted_type: "fire",
+        },
+        "torrent" => &Pinch {
+            name: "torrent",
+            boosted_type: "water",
+        },
+        "overgrow" => &Pinch {
+            name: "overgrow",
+            boosted_type: "grass",
+        },
+        "swarm" => &Pinch {
+            name: "swarm",
+            boosted_type: "bug",
+        },
+        "sturdy" => &Sturdy,
+        "static" => &Static,
+        "thick-fat" => &ThickFat,
+        "speed-boost" => &SpeedBoost,
+        _ => return None,
+    };
+    Some(ability)
+}
+
+/// Floats above the ground, out of reach of ground moves.
+#[derive(Debug)]
+struct Levitate;
+
+impl Ability for Levitate {
+    fn name(&self) -> &'static str {
+        "levitate"
+    }
+
+    fn is_immune(&self, attack_type: &str) -> bool {
+        attack_type == "ground"
+    }
+}
+
+/// Lowers the opponent's attack by one stage on switch-in.
+#[derive(Debug)]
+struct Intimidate;
+
+impl Ability for Intimidate {
+    fn name(&self) -> &'static str {
+        "intimidate"
+    }
+
+    fn on_switch_in(&self, _owner: &mut BattlePokemon, opponent: &mut BattlePokemon) -> bool {
+        opponent.get_stages_mut().change("attack", -1) != 0
+    }
+}
+
+/// Blaze, Torrent, Overgrow and Swarm: moves of one type hit 50% harder at 1/3 HP.
+#[derive(Debug)]
+struct Pinch {
+    name: &'static str,
+    boosted_type: &'static str,
+}
+
+impl Ability for Pinch {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn stat_modifier(&self, owner: &BattlePokemon, stat: &str, used_move: &Move) -> f64 {
+        let attacking = stat == "attack" || stat == "special-attack";
+        let in_a_pinch = owner.get_current_hp() * 3 <= owner.get_max_hp();
+        if attacking && in_a_pinch && used_move.get_type() == self.boosted_type {
+            1.5
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Survives any hit from full HP with 1 HP left.
+#[derive(Debug)]
+struct Sturdy;
+
+impl Ability for Sturdy {
+    fn name(&self) -> &'static str {
+        "sturdy"
+    }
+
+    fn on_damage_taken(
+        &self,
+        owner: &BattlePokemon,
+        _attacker: &mut BattlePokemon,
+        _used_move: &Move,
+        damage: &mut i64,
+        _rng: &mut dyn RngCore,
+    ) -> bool {
+        let full_hp = owner.get_current_hp() == owner.get_max_hp();
+        if full_hp && *damage >= owner.get_current_hp() {
+            *damage = owner.get_current_hp() - 1;
+            return true;
+        }
+        false
+    }
+}
+
+/// Can paralyze Pokemon hitting the owner with physical moves, our stand-in for contact.
+#[derive(Debug)]
+struct Static;
+
+impl Ability for Static {
+    fn name(&self) -> &'static str {
+        "static"
+    }
+
+    fn on_damage_taken(
+        &self,
+        _owner: &BattlePokemon,
+        attacker: &mut BattlePokemon,
+        used_move: &Move,
+        _damage: &mut i64,
+        rng: &mut dyn RngCore,
+    ) -> bool {
+        used_move.get_damage_class() == DamageClass::Physical
+            && rng.random_bool(STATIC_CHANCE)
+            && attacker.inflict_status(StatusCondition::Paralysis, rng)
+    }
+}
+
+/// Halves damage from fire and ice moves, by doubling the defending stat.
+#[derive(Debug)]
+struct ThickFat;
+
+impl Ability for ThickFat {
+    fn name(&self) -> &'static str {
+        "thick-fat"
+    }
+
+    fn stat_modifier(&self, _owner: &BattlePokemon, stat: &str, used_move: &Move) -> f64 {
+        let defending = stat == "defense" || stat == "special-defense";
+        if defending && matches!(used_move.get_type(), "fire" | "ice") {
+            2.0
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Raises the owner's speed by one stage at the end of every round.
+#[derive(Debug)]
+struct SpeedBoost;
+
+impl Ability for SpeedBoost {
+    fn name(&self) -> &'static str {
+        "speed-boost"
+    }
+
+    fn on_end_of_turn(&self, owner: &mut BattlePokemon) -> bool {
+        owner.get_stages_mut().change("speed", 1) != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_utils::{create_test_move, create_test_pokemon};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(lookup("torrent").unwrap().name(), "torrent");
+        assert!(lookup("levitate").unwrap().is_immune("ground"));
+        assert!(lookup("run-away").is_none());
+    }
+
+    #[test]
+    fn test_pinch_abilities_boost_their_type_at_low_hp() {
+        let charmander = create_test_pokemon("charmander", 39, 52, 43, 65, "fire");
+        let mut owner = BattlePokemon::new(&charmander);
+        let blaze = lookup("blaze").unwrap();
+        let ember = create_test_move("ember", 40, "fire", DamageClass::Special);
+        let scratch = create_test_move("scratch", 40, "normal", DamageClass::Physical);
+
+        assert_eq!(blaze.stat_modifier(&owner, "special-attack", &ember), 1.0);
+        owner.take_damage(owner.get_max_hp() * 2 / 3 + 1);
+        assert_eq!(blaze.stat_modifier(&owner, "special-attack", &ember), 1.5);
+        assert_eq!(blaze.stat_modifier(&owner, "attack", &scratch), 1.0);
+        assert_eq!(blaze.stat_modifier(&owner, "defense", &ember), 1.0);
+    }
+
+    #[test]
+    fn test_sturdy_only_holds_on_from_full_hp() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let geodude = create_test_pokemon("geodude", 40, 80, 100, 20, "rock");
+        let mut owner = BattlePokemon::new(&geodude);
+        let mut attacker = BattlePokemon::new(&geodude);
+        let sturdy = lookup("sturdy").unwrap();
+        let surf = create_test_move("surf", 90, "water", DamageClass::Special);
+
+        let mut damage = 500;
+        assert!(sturdy.on_damage_taken(&owner, &mut attacker, &surf, &mut damage, &mut rng));
+        assert_eq!(damage, owner.get_max_hp() - 1);
+
+        owner.take_damage(1);
+        let mut damage = 500;
+        assert!(!sturdy.on_damage_taken(&owner, &mut attacker, &surf, &mut damage, &mut rng));
+        assert_eq!(damage, 500);
+    }
+
+    #[test]
+    fn test_static_paralyzes_physical_attackers() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let pikachu = create_test_pokemon("pikachu", 35, 55, 40, 90, "electric");
+        let eevee = create_test_pokemon("eevee", 55, 55, 50, 55, "normal");
+        let owner = BattlePokemon::new(&pikachu);
+        let static_ability = lookup("static").unwrap();
+        let tackle = create_test_move("tackle", 40, "normal", DamageClass::Physical);
+        let swift = create_test_move("swift", 60, "normal", DamageClass::Special);
+
+        let mut attacker = BattlePokemon::new(&eevee);
+        for _ in 0..20 {
+            static_ability.on_damage_taken(&owner, &mut attacker, &swift, &mut 10, &mut rng);
+        }
+        assert_eq!(attacker.get_status(), None);
+
+        let paralyzed = (0..20).any(|_| {
+            static_ability.on_damage_taken(&owner, &mut attacker, &tackle, &mut 10, &mut rng)
+        });
+        assert!(paralyzed);
+        assert_eq!(attacker.get_status(), Some(StatusCondition::Paralysis));
+    }
+}
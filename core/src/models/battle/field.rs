@@ -0,0 +1,421 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::moves::{DamageClass, Move};
+use crate::models::pokemon::BattlePokemon;
+use crate::models::status::StatusCondition;
+
+/// Damage boost of moves that match the weather or terrain.
+const BOOST: f64 = 1.5;
+const TERRAIN_BOOST: f64 = 1.3;
+
+/// Weather affecting both sides of the battle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Weather {
+    /// Fire moves hit harder, water moves weaker.
+    Sun,
+    /// Water moves hit harder, fire moves weaker.
+    Rain,
+    /// Hurts everything but rock, ground and steel types, and raises the
+    /// special defense of rock types.
+    Sandstorm,
+    /// Hurts everything but ice types.
+    Hail,
+    /// Raises the defense of ice types.
+    Snow,
+}
+
+impl Weather {
+    /// Multiplier of damage `used_move` deals to `defender`. Defense boosts
+    /// show up as less damage.
+    pub fn damage_modifier(&self, used_move: &Move, defender: &BattlePokemon) -> f64 {
+        let defender_types = defender.get_types();
+        match (self, used_move.get_type()) {
+            (Weather::Sun, "fire") | (Weather::Rain, "water") => BOOST,
+            (Weather::Sun, "water") | (Weather::Rain, "fire") => 1.0 / BOOST,
+            (Weather::Sandstorm, _)
+                if used_move.get_damage_class() == DamageClass::Special
+                    && defender_types.contains(&"rock") =>
+            {
+                1.0 / BOOST
+            }
+            (Weather::Snow, _)
+                if used_move.get_damage_class() == DamageClass::Physical
+                    && defender_types.contains(&"ice") =>
+            {
+                1.0 / BOOST
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// HP `pokemon` loses to the weather at the end of a round.
+    pub fn chip_damage(&self, pokemon: &BattlePokemon) -> i64 {
+        let immune_types: &[&str] = match self {
+            Weather::Sandstorm => &["rock", "ground", "steel"],
+            Weather::Hail => &["ice"],
+            _ => return 0,
+        };
+        if pokemon.get_types().iter().any(|t| immune_types.contains(t)) {
+            return 0;
+        }
+        (pokemon.get_max_hp() / 16).max(1)
+    }
+}
+
+impl fmt::Display for Weather {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Weather::Sun => "sun",
+            Weather::Rain => "rain",
+            Weather::Sandstorm => "sandstorm",
+            Weather::Hail => "hail",
+            Weather::Snow => "snow",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Weather {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sun" => Ok(Weather::Sun),
+            "rain" => Ok(Weather::Rain),
+            "sandstorm" => Ok(Weather::Sandstorm),
+            "hail" => Ok(Weather::Hail),
+            "snow" => Ok(Weather::Snow),
+            other => Err(format!(
+                "unknown weather '{}', expected sun, rain, sandstorm, hail or snow",
+                other
+            )),
+        }
+    }
+}
+
+/// Terrain covering the battlefield. It only affects grounded Pokemon, so
+/// flying types and Pokemon with Levitate are out of its reach.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Terrain {
+    /// Electric moves hit harder and Pokemon cannot fall asleep.
+    Electric,
+    /// Grass moves hit harder and Pokemon heal a bit every round.
+    Grassy,
+    /// Psychic moves hit harder and priority moves fail.
+    Psychic,
+    /// Dragon moves deal half damage and no status conditions stick.
+    Misty,
+}
+
+impl Terrain {
+    /// Multiplier of damage `used_move` deals from `attacker` to `defender`.
+    pub fn damage_modifier(
+        &self,
+        used_move: &Move,
+        attacker: &BattlePokemon,
+        defender: &BattlePokemon,
+    ) -> f64 {
+        let boosted_type = match self {
+            Terrain::Electric => "electric",
+            Terrain::Grassy => "grass",
+            Terrain::Psychic => "psychic",
+            Terrain::Misty if used_move.get_type() == "dragon" && is_grounded(defender) => {
+                return 0.5;
+            }
+            Terrain::Misty => return 1.0,
+        };
+        if used_move.get_type() == boosted_type && is_grounded(attacker) {
+            TERRAIN_BOOST
+        } else {
+            1.0
+        }
+    }
+
+    /// Whether `used_move` fails against `defender` altogether.
+    pub fn blocks_move(&self, used_move: &Move, defender: &BattlePokemon) -> bool {
+        *self == Terrain::Psychic && used_move.get_priority() > 0 && is_grounded(defender)
+    }
+
+    /// Whether `status` cannot be inflicted on `pokemon`.
+    pub fn prevents_status(&self, status: StatusCondition, pokemon: &BattlePokemon) -> bool {
+        let prevented = match self {
+            Terrain::Electric => status == StatusCondition::Sleep,
+            Terrain::Misty => true,
+            _ => false,
+        };
+        prevented && is_grounded(pokemon)
+    }
+
+    /// HP `pokemon` restores at the end of a round.
+    pub fn healing(&self, pokemon: &BattlePokemon) -> i64 {
+        if *self == Terrain::Grassy && is_grounded(pokemon) {
+            (pokemon.get_max_hp() / 16).max(1)
+        } else {
+            0
+        }
+    }
+}
+
+impl fmt::Display for Terrain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Terrain::Electric => "electric",
+            Terrain::Grassy => "grassy",
+            Terrain::Psychic => "psychic",
+            Terrain::Misty => "misty",
+        };
+        write!(f, "{} terrain", name)
+    }
+}
+
+impl FromStr for Terrain {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim_end_matches("-terrain") {
+            "electric" => Ok(Terrain::Electric),
+            "grassy" => Ok(Terrain::Grassy),
+            "psychic" => Ok(Terrain::Psychic),
+            "misty" => Ok(Terrain::Misty),
+            other => Err(format!(
+                "unknown terrain '{}', expected electric, grassy, psychic or misty",
+                other
+            )),
+        }
+    }
+}
+
+/// Flying types and Pokemon with Levitate float above the terrain.
+fn is_grounded(pokemon: &BattlePokemon) -> bool {
+    !pokemon.get_types().contains(&"flying") && !pokemon.is_immune_by_ability("ground")
+}
+
+/// Weather and terrain a tournament is fought in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Arena {
+    #[serde(default)]
+    pub weather: Option<Weather>,
+    #[serde(default)]
+    pub terrain: Option<Terrain>,
+    /// Rounds weather and terrain last, the whole battle when absent.
+    #[serde(default)]
+    pub turns: Option<u32>,
+}
+
+impl Arena {
+    pub fn with_weather(mut self, weather: Option<Weather>) -> Self {
+        self.weather = weather;
+        self
+    }
+
+    pub fn with_terrain(mut self, terrain: Option<Terrain>) -> Self {
+        self.terrain = terrain;
+        self
+    }
+
+    pub fn with_turns(mut self, turns: Option<u32>) -> Self {
+        self.turns = turns;
+        self
+    }
+
+    /// No weather and no terrain.
+    pub fn is_neutral(&self) -> bool {
+        self.weather.is_none() && self.terrain.is_none()
+    }
+}
+
+impl fmt::Display for Arena {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let effects: Vec<String> = self
+            .weather
+            .map(|w| w.to_string())
+            .into_iter()
+            .chain(self.terrain.map(|t| t.to_string()))
+            .collect();
+        if effects.is_empty() {
+            return write!(f, "neutral");
+        }
+        write!(f, "{}", effects.join(", "))?;
+        if let Some(turns) = self.turns {
+            write!(f, " for {} rounds", turns)?;
+        }
+        Ok(())
+    }
+}
+
+/// Weather and terrain of a battle in progress, counting down their turns.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Field {
+    weather: Option<(Weather, Option<u32>)>,
+    terrain: Option<(Terrain, Option<u32>)>,
+}
+
+impl Field {
+    pub fn new(arena: &Arena) -> Self {
+        Self {
+            weather: arena.weather.map(|weather| (weather, arena.turns)),
+            terrain: arena.terrain.map(|terrain| (terrain, arena.turns)),
+        }
+    }
+
+    pub fn get_weather(&self) -> Option<Weather> {
+        self.weather.map(|(weather, _)| weather)
+    }
+
+    pub fn get_terrain(&self) -> Option<Terrain> {
+        self.terrain.map(|(terrain, _)| terrain)
+    }
+
+    /// Sets the weather for `turns` rounds, or until replaced when `None`.
+    pub fn set_weather(&mut self, weather: Weather, turns: Option<u32>) {
+        self.weather = Some((weather, turns));
+    }
+
+    /// Sets the terrain for `turns` rounds, or until replaced when `None`.
+    pub fn set_terrain(&mut self, terrain: Terrain, turns: Option<u32>) {
+        self.terrain = Some((terrain, turns));
+    }
+
+    /// Multiplier of damage `used_move` deals from `attacker` to `defender`,
+    /// zero if the move fails on this field.
+    pub fn damage_modifier(
+        &self,
+        used_move: &Move,
+        attacker: &BattlePokemon,
+        defender: &BattlePokemon,
+    ) -> f64 {
+        if self.blocks_move(used_move, defender) {
+            return 0.0;
+        }
+        let weather = self
+            .get_weather()
+            .map_or(1.0, |w| w.damage_modifier(used_move, defender));
+        let terrain = self
+            .get_terrain()
+            .map_or(1.0, |t| t.damage_modifier(used_move, attacker, defender));
+        weather * terrain
+    }
+
+    pub fn blocks_move(&self, used_move: &Move, defender: &BattlePokemon) -> bool {
+        self.get_terrain()
+            .is_some_and(|t| t.blocks_move(used_move, defender))
+    }
+
+    pub fn prevents_status(&self, status: StatusCondition, pokemon: &BattlePokemon) -> bool {
+        self.get_terrain()
+            .is_some_and(|t| t.prevents_status(status, pokemon))
+    }
+
+    /// Counts down one round and returns the weather and terrain that ran
+    /// out.
+    pub fn end_round(&mut self) -> (Option<Weather>, Option<Terrain>) {
+        (countdown(&mut self.weather), countdown(&mut self.terrain))
+    }
+}
+
+/// Counts down the turns of a field effect, removing and returning it when
+/// none are left.
+fn countdown<T: Copy>(effect: &mut Option<(T, Option<u32>)>) -> Option<T> {
+    match effect {
+        Some((kind, Some(turns))) => {
+            let kind = *kind;
+            *turns = turns.saturating_sub(1);
+            if *turns == 0 {
+                *effect = None;
+                return Some(kind);
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_utils::{create_test_move, create_test_pokemon};
+
+    #[test]
+    fn test_weather_damage_and_chip() {
+        let charizard = create_test_pokemon("charizard", 78, 84, 78, 100, "fire");
+        let golem = create_test_pokemon("golem", 80, 120, 130, 45, "rock");
+        let charizard = BattlePokemon::new(&charizard);
+        let golem = BattlePokemon::new(&golem);
+        let flamethrower = create_test_move("flamethrower", 90, "fire", DamageClass::Special);
+        let surf = create_test_move("surf", 90, "water", DamageClass::Special);
+
+        assert_eq!(Weather::Sun.damage_modifier(&flamethrower, &golem), 1.5);
+        assert_eq!(Weather::Sun.damage_modifier(&surf, &golem), 1.0 / 1.5);
+        assert_eq!(Weather::Rain.damage_modifier(&surf, &charizard), 1.5);
+        assert_eq!(Weather::Sandstorm.damage_modifier(&surf, &golem), 1.0 / 1.5);
+        assert_eq!(Weather::Sandstorm.damage_modifier(&surf, &charizard), 1.0);
+
+        assert_eq!(
+            Weather::Sandstorm.chip_damage(&charizard),
+            charizard.get_max_hp() / 16
+        );
+        assert_eq!(Weather::Sandstorm.chip_damage(&golem), 0);
+        assert_eq!(Weather::Rain.chip_damage(&charizard), 0);
+        assert_eq!("hail".parse(), Ok(Weather::Hail));
+        assert!("fog".parse::<Weather>().is_err());
+    }
+
+    #[test]
+    fn test_terrain_only_affects_grounded_pokemon() {
+        let pikachu = create_test_pokemon("pikachu", 35, 55, 40, 90, "electric");
+        let pidgey = create_test_pokemon("pidgey", 40, 45, 40, 56, "flying");
+        let pikachu = BattlePokemon::new(&pikachu);
+        let pidgey = BattlePokemon::new(&pidgey);
+        let thunderbolt = create_test_move("thunderbolt", 90, "electric", DamageClass::Special);
+        let mut quick_attack =
+            create_test_move("quick-attack", 40, "normal", DamageClass::Physical);
+        quick_attack.priority = 1;
+
+        let electric = Terrain::Electric;
+        assert_eq!(
+            electric.damage_modifier(&thunderbolt, &pikachu, &pidgey),
+            1.3
+        );
+        assert_eq!(
+            electric.damage_modifier(&thunderbolt, &pidgey, &pikachu),
+            1.0
+        );
+        assert!(electric.prevents_status(StatusCondition::Sleep, &pikachu));
+        assert!(!electric.prevents_status(StatusCondition::Sleep, &pidgey));
+        assert!(!electric.prevents_status(StatusCondition::Burn, &pikachu));
+
+        assert!(Terrain::Psychic.blocks_move(&quick_attack, &pikachu));
+        assert!(!Terrain::Psychic.blocks_move(&quick_attack, &pidgey));
+        assert_eq!(Terrain::Grassy.healing(&pikachu), pikachu.get_max_hp() / 16);
+        assert_eq!(Terrain::Grassy.healing(&pidgey), 0);
+        assert_eq!("misty-terrain".parse(), Ok(Terrain::Misty));
+    }
+
+    #[test]
+    fn test_field_effects_run_out() {
+        let arena = Arena::default()
+            .with_weather(Some(Weather::Rain))
+            .with_terrain(Some(Terrain::Grassy))
+            .with_turns(Some(2));
+        assert_eq!(arena.to_string(), "rain, grassy terrain for 2 rounds");
+        let mut field = Field::new(&arena);
+
+        assert_eq!(field.end_round(), (None, None));
+        assert_eq!(
+            field.end_round(),
+            (Some(Weather::Rain), Some(Terrain::Grassy))
+        );
+        assert_eq!(field.get_weather(), None);
+
+        field.set_weather(Weather::Sun, None);
+        for _ in 0..10 {
+            assert_eq!(field.end_round(), (None, None));
+        }
+        assert_eq!(field.get_weather(), Some(Weather::Sun));
+    }
+}
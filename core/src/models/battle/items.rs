@@ -0,0 +1,432 @@
+use std::fmt::Debug;
+
+use crate::errors::{PokeFightError, Result};
+use crate::models::moves::Move;
+use crate::models::pokemon::BattlePokemon;
+use crate::models::status::StatusCondition;
+
+/// A held item, hooking into the battle like an [`Ability`](super::abilities::Ability).
+pub trait HeldItem: Debug + Send + Sync {
+    /// PokeAPI name, e.g. `leftovers`.
+    fn name(&self) -> &'static str;
+
+    /// Consumed after its first activation, like berries.
+    fn is_single_use(&self) -> bool {
+        false
+    }
+
+    /// Multiplier of the holder's `stat`.
+    fn stat_modifier(&self, _stat: &str) -> f64 {
+        1.0
+    }
+
+    /// Whether the holder is locked into the first move it uses.
+    fn locks_move(&self) -> bool {
+        false
+    }
+
+    /// Multiplier of the damage the holder deals with `used_move`.
+    fn damage_modifier(&self, _used_move: &Move) -> f64 {
+        1.0
+    }
+
+    /// Before the holder takes `damage`. Can change the damage.
+    fn on_damage_taken(&self, _owner: &BattlePokemon, _damage: &mut i64) -> bool {
+        false
+    }
+
+    /// After the holder dealt `damage` with an attack.
+    fn after_attack(&self, _owner: &mut BattlePokemon, _damage: i64) -> bool {
+        false
+    }
+
+    /// Whenever the holder's HP or status might have changed.
+    fn on_update(&self, _owner: &mut BattlePokemon) -> bool {
+        false
+    }
+
+    /// At the end of every round the holder is still standing.
+    fn on_end_of_turn(&self, _owner: &mut BattlePokemon) -> bool {
+        false
+    }
+}
+
+/// Looks up an item by its PokeAPI name, or its display name like `Choice Band`.
+pub fn lookup(name: &str) -> Option<&'static dyn HeldItem> {
+    let name = name.trim().to_lowercase().replace(' ', "-");
+    let item: &'static dyn HeldItem = match name.as_str() {
+        "leftovers" => &Leftovers,
+        "choice-band" => &Choice {
+            name: "choice-band",
+            stat: "attack",
+        },
+        "choice-specs" => &Choice {
+            name: "choice-specs",
+            stat: "special-attack",
+        },
+        "choice-scarf" => &Choice {
+            name: "choice-scarf",
+            stat: "speed",
+        },
+        "focus-sash" => &FocusSash,
+        "life-orb" => &LifeOrb,
+        "silk-scarf" => &TypeBoost {
+            name: "silk-scarf",
+            boosted_type: "normal",
+        },
+        "charcoal" => &TypeBoost {
+            name: "charcoal",
+            boosted_type: "fire",
+        },
+        "mystic-water" => &TypeBoost {
+            name: "mystic-water",
+            boosted_type: "water",
+        },
+        "miracle-seed" => &TypeBoost {
+            name: "miracle-seed",
+            boosted_type: "grass",
+        },
+        "magnet" => &TypeBoost {
+            name: "magnet",
+            boosted_type: "electric",
+        },
+        "never-melt-ice" => &TypeBoost {
+            name: "never-melt-ice",
+            boosted_type: "ice",
+        },
+        "black-belt" => &TypeBoost {
+            name: "black-belt",
+            boosted_type: "fighting",
+        },
+        "poison-barb" => &TypeBoost {
+            name: "poison-barb",
+            boosted_type: "poison",
+        },
+        "soft-sand" => &TypeBoost {
+            name: "soft-sand",
+            boosted_type: "ground",
+        },
+        "sharp-beak" => &TypeBoost {
+            name: "sharp-beak",
+            boosted_type: "flying",
+        },
+        "twisted-spoon" => &TypeBoost {
+            name: "twisted-spoon",
+            boosted_type: "psychic",
+        },
+        "silver-powder" => &TypeBoost {
+            name: "silver-powder",
+            boosted_type: "bug",
+        },
+        "hard-stone" => &TypeBoost {
+            name: "hard-stone",
+            boosted_type: "rock",
+        },
+        "spell-tag" => &TypeBoost {
+            name: "spell-tag",
+            boosted_type: "ghost",
+        },
+        "dragon-fang" => &TypeBoost {
+            name: "dragon-fang",
+            boosted_type: "dragon",
+        },
+        "black-glasses" => &TypeBoost {
+            name: "black-glasses",
+            boosted_type: "dark",
+        },
+        "metal-coat" => &TypeBoost {
+            name: "metal-coat",
+            boosted_type: "steel",
+        },
+        "fairy-feather" => &TypeBoost {
+            name: "fairy-feather",
+            boosted_type: "fairy",
+        },
+        "sitrus-berry" => &HealingBerry {
+            name: "sitrus-berry",
+            heal: Heal::Quarter,
+        },
+        "oran-berry" => &HealingBerry {
+            name: "oran-berry",
+            heal: Heal::Fixed(10),
+        },
+        "lum-berry" => &CureBerry {
+            name: "lum-berry",
+            cures: None,
+        },
+        "cheri-berry" => &CureBerry {
+            name: "cheri-berry",
+            cures: Some(StatusCondition::Paralysis),
+        },
+        "chesto-berry" => &CureBerry {
+            name: "chesto-berry",
+            cures: Some(StatusCondition::Sleep),
+        },
+        "pecha-berry" => &CureBerry {
+            name: "pecha-berry",
+            cures: Some(StatusCondition::Poison),
+        },
+        "rawst-berry" => &CureBerry {
+            name: "rawst-berry",
+            cures: Some(StatusCondition::Burn),
+        },
+        "aspear-berry" => &CureBerry {
+            name: "aspear-berry",
+            cures: Some(StatusCondition::Freeze),
+        },
+        _ => return None,
+    };
+    Some(item)
+}
+
+/// Like [`lookup`], but unknown items are an error.
+pub fn parse(name: &str) -> Result<&'static dyn HeldItem> {
+    lookup(name).ok_or_else(|| PokeFightError::UnknownItem(name.to_string()))
+}
+
+/// Restores 1/16 of the holder's max HP at the end of every round.
+#[derive(Debug)]
+struct Leftovers;
+
+impl HeldItem for Leftovers {
+    fn name(&self) -> &'static str {
+        "leftovers"
+    }
+
+    fn on_end_of_turn(&self, owner: &mut BattlePokemon) -> bool {
+        owner.heal((owner.get_max_hp() / 16).max(1)) > 0
+    }
+}
+
+/// Choice Band, Specs and Scarf: a 50% stat boost that locks the holder into one move.
+#[derive(Debug)]
+struct Choice {
+    name: &'static str,
+    stat: &'static str,
+}
+
+impl HeldItem for Choice {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn stat_modifier(&self, stat: &str) -> f64 {
+        if stat == self.stat {
+            1.5
+        } else {
+            1.0
+        }
+    }
+
+    fn locks_move(&self) -> bool {
+        true
+    }
+}
+
+/// Survives a hit that would knock the holder out from full HP, once.
+#[derive(Debug)]
+struct FocusSash;
+
+impl HeldItem for FocusSash {
+    fn name(&self) -> &'static str {
+        "focus-sash"
+    }
+
+    fn is_single_use(&self) -> bool {
+        true
+    }
+
+    fn on_damage_taken(&self, owner: &BattlePokemon, damage: &mut i64) -> bool {
+        let full_hp = owner.get_current_hp() == owner.get_max_hp();
+        if full_hp && *damage >= owner.get_current_hp() {
+            *damage = owner.get_current_hp() - 1;
+            return true;
+        }
+        false
+    }
+}
+
+/// Attacks deal 30% more damage, but cost the holder 1/10 of its max HP.
+#[derive(Debug)]
+struct LifeOrb;
+
+impl HeldItem for LifeOrb {
+    fn name(&self) -> &'static str {
+        "life-orb"
+    }
+
+    fn damage_modifier(&self, _used_move: &Move) -> f64 {
+        1.3
+    }
+
+    fn after_attack(&self, owner: &mut BattlePokemon, damage: i64) -> bool {
+        if damage > 0 {
+            owner.take_damage((owner.get_max_hp() / 10).max(1));
+            return true;
+        }
+        false
+    }
+}
+
+/// Moves of one type deal 20% more damage, e.g. Charcoal for fire.
+#[derive(Debug)]
+struct TypeBoost {
+    name: &'static str,
+    boosted_type: &'static str,
+}
+
+impl HeldItem for TypeBoost {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn damage_modifier(&self, used_move: &Move) -> f64 {
+        if used_move.get_type() == self.boosted_type {
+            1.2
+        } else {
+            1.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Heal {
+    Quarter,
+    Fixed(i64),
+}
+
+/// Restores HP once the holder drops to half its max HP or below.
+#[derive(Debug)]
+struct HealingBerry {
+    name: &'static str,
+    heal: Heal,
+}
+
+impl HeldItem for HealingBerry {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn is_single_use(&self) -> bool {
+        true
+    }
+
+    fn on_update(&self, owner: &mut BattlePokemon) -> bool {
+        if owner.is_fainted() || owner.get_current_hp() * 2 > owner.get_max_hp() {
+            return false;
+        }
+        let amount = match self.heal {
+            Heal::Quarter => owner.get_max_hp() / 4,
+            Heal::Fixed(amount) => amount,
+        };
+        owner.heal(amount) > 0
+    }
+}
+
+/// Cures one status condition, or any for `None` like the Lum Berry.
+#[derive(Debug)]
+struct CureBerry {
+    name: &'static str,
+    cures: Option<StatusCondition>,
+}
+
+impl HeldItem for CureBerry {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn is_single_use(&self) -> bool {
+        true
+    }
+
+    fn on_update(&self, owner: &mut BattlePokemon) -> bool {
+        match owner.get_status() {
+            Some(status) if self.cures.is_none_or(|cures| cures == status) => {
+                owner.cure_status();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::moves::DamageClass;
+    use crate::models::test_utils::{create_test_move, create_test_pokemon};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_lookup_and_parse() {
+        assert_eq!(lookup("Choice Band").unwrap().name(), "choice-band");
+        assert_eq!(lookup("charcoal").unwrap().name(), "charcoal");
+        assert!(lookup("master-ball").is_none());
+        assert!(matches!(
+            parse("master-ball"),
+            Err(PokeFightError::UnknownItem(name)) if name == "master-ball"
+        ));
+    }
+
+    #[test]
+    fn test_leftovers_and_berries_heal() {
+        let snorlax = create_test_pokemon("snorlax", 160, 110, 65, 30, "normal");
+        let mut owner = BattlePokemon::new(&snorlax);
+        let max_hp = owner.get_max_hp();
+
+        assert!(!Leftovers.on_end_of_turn(&mut owner));
+        owner.take_damage(100);
+        assert!(Leftovers.on_end_of_turn(&mut owner));
+        assert_eq!(owner.get_current_hp(), max_hp - 100 + max_hp / 16);
+
+        let sitrus = lookup("sitrus-berry").unwrap();
+        assert!(!sitrus.on_update(&mut owner));
+        owner.take_damage(max_hp / 2);
+        let hp = owner.get_current_hp();
+        assert!(sitrus.on_update(&mut owner));
+        assert_eq!(owner.get_current_hp(), hp + max_hp / 4);
+        assert!(sitrus.is_single_use());
+    }
+
+    #[test]
+    fn test_cure_berries_cure_their_condition() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let eevee = create_test_pokemon("eevee", 55, 55, 50, 55, "normal");
+        let mut owner = BattlePokemon::new(&eevee);
+        owner.inflict_status(StatusCondition::Burn, &mut rng);
+
+        assert!(!lookup("cheri-berry").unwrap().on_update(&mut owner));
+        assert!(lookup("rawst-berry").unwrap().on_update(&mut owner));
+        assert_eq!(owner.get_status(), None);
+
+        owner.inflict_status(StatusCondition::Sleep, &mut rng);
+        assert!(lookup("lum-berry").unwrap().on_update(&mut owner));
+        assert_eq!(owner.get_status(), None);
+    }
+
+    #[test]
+    fn test_damage_items() {
+        let ember = create_test_move("ember", 40, "fire", DamageClass::Special);
+        let tackle = create_test_move("tackle", 40, "normal", DamageClass::Physical);
+
+        let charcoal = lookup("charcoal").unwrap();
+        assert_eq!(charcoal.damage_modifier(&ember), 1.2);
+        assert_eq!(charcoal.damage_modifier(&tackle), 1.0);
+
+        let charmander = create_test_pokemon("charmander", 39, 52, 43, 65, "fire");
+        let mut owner = BattlePokemon::new(&charmander);
+        assert_eq!(LifeOrb.damage_modifier(&tackle), 1.3);
+        assert!(!LifeOrb.after_attack(&mut owner, 0));
+        assert!(LifeOrb.after_attack(&mut owner, 30));
+        assert_eq!(
+            owner.get_current_hp(),
+            owner.get_max_hp() - owner.get_max_hp() / 10
+        );
+
+        let mut damage = 500;
+        let fresh = BattlePokemon::new(&charmander);
+        assert!(FocusSash.on_damage_taken(&fresh, &mut damage));
+        assert_eq!(damage, fresh.get_max_hp() - 1);
+    }
+}
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Position, RoundReport};
+use crate::models::status::StatusCondition;
+
+/// A Pokemon in battle, by the side and slot it fights from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fighter {
+    /// 0 for the first trainer of the match, 1 for the second.
+    pub side: usize,
+    /// Always 0 in singles, 0 or 1 in doubles.
+    pub slot: usize,
+    pub name: String,
+}
+
+impl Fighter {
+    pub fn new(position: Position, name: &str) -> Self {
+        Self {
+            side: position.side,
+            slot: position.slot,
+            name: name.to_string(),
+        }
+    }
+}
+
+/// Something that happened in a battle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum BattleEvent {
+    /// A new round begins, counting from 1.
+    TurnStart {
+        turn: usize,
+    },
+    /// A Pokemon entered the battle, as a lead, a switch or a replacement.
+    SwitchIn {
+        fighter: Fighter,
+        hp: i64,
+        max_hp: i64,
+    },
+    /// A Pokemon got to use a move. What it did to each target follows.
+    MoveUsed {
+        fighter: Fighter,
+        move_name: String,
+        move_type: String,
+    },
+    Miss {
+        fighter: Fighter,
+        target: Fighter,
+    },
+    /// The damage right after this one was a critical hit.
+    CriticalHit {
+        target: Fighter,
+    },
+    /// A move hit. `effectiveness` is the type multiplier, e.g. 2.0 for
+    /// super effective or 0.5 for not very effective.
+    Damage {
+        target: Fighter,
+        damage: i64,
+        /// HP left afterwards.
+        hp: i64,
+        effectiveness: f64,
+    },
+    /// A Pokemon could not move because of its status condition.
+    Immobilized {
+        fighter: Fighter,
+        status: StatusCondition,
+    },
+    StatusApplied {
+        target: Fighter,
+        status: StatusCondition,
+    },
+    /// A Pokemon woke up, thawed out or was cured by a berry.
+    StatusCured {
+        fighter: Fighter,
+    },
+    StatChanged {
+        fighter: Fighter,
+        stat: String,
+        stages: i8,
+    },
+    /// HP changed other than by a move's damage, e.g. `burn`, `sandstorm`,
+    /// `grassy terrain` or `leftovers`. `hp` is the HP left afterwards.
+    HpChanged {
+        fighter: Fighter,
+        hp: i64,
+        cause: String,
+    },
+    AbilityActivated {
+        fighter: Fighter,
+        ability: String,
+    },
+    ItemActivated {
+        fighter: Fighter,
+        item: String,
+    },
+    Faint {
+        fighter: Fighter,
+    },
+    /// The round limit ended the battle before either side ran out of
+    /// Pokemon. The side with more of its team's HP left won.
+    TurnLimit {
+        turns: usize,
+    },
+}
+
+/// Every event of a battle, in the order they happened.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct BattleLog {
+    pub events: Vec<BattleEvent>,
+}
+
+impl BattleLog {
+    pub fn new(reports: &[RoundReport]) -> Self {
+        Self {
+            events: reports
+                .iter()
+                .flat_map(|report| report.events.iter().cloned())
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
@@ -0,0 +1,502 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use rand::seq::IndexedRandom;
+use rand::RngCore;
+
+use super::{
+    best_attack, best_switch, choose_move, damage_multiplier, expected_damage, hit_chance, matchup,
+    threat, Action, Position, Turn,
+};
+use crate::errors::{PokeFightError, Result};
+use crate::models::moves::Move;
+use crate::models::pokemon::BattlePokemon;
+
+/// Decides what a trainer's Pokemon do in battle. Every round, the strategy
+/// is asked for each of the trainer's Pokemon in battle before anyone acts.
+pub trait BattleStrategy: Debug + Send + Sync {
+    /// Name the strategy is picked by, e.g. `greedy`.
+    fn name(&self) -> String;
+
+    /// A move aimed at one of the opponents, or a switch to a team member
+    /// from the bench. Switches to anyone else are ignored.
+    fn choose_action(&self, turn: &Turn, rng: &mut dyn RngCore) -> Action;
+
+    /// Team member from the bench that replaces the fainted Pokemon, by
+    /// default the one with the best matchup.
+    fn choose_replacement(&self, turn: &Turn, _rng: &mut dyn RngCore) -> Option<usize> {
+        best_switch(turn).map(|(index, _)| index)
+    }
+}
+
+/// Looks up a built-in strategy by name: `random`, `greedy`, `type-aware`
+/// or `lookahead`, optionally with a depth from 1 to [`MAX_LOOKAHEAD_DEPTH`]
+/// like `lookahead-3`.
+pub fn lookup(name: &str) -> Option<Arc<dyn BattleStrategy>> {
+    let name = name.trim().to_lowercase();
+    let strategy: Arc<dyn BattleStrategy> = match name.as_str() {
+        "random" => Arc::new(RandomStrategy),
+        "greedy" => Arc::new(Greedy),
+        "type-aware" => Arc::new(TypeAware),
+        "lookahead" => Arc::new(Lookahead::default()),
+        other => {
+            let depth = other.strip_prefix("lookahead-")?.parse().ok()?;
+            if !(1..=MAX_LOOKAHEAD_DEPTH).contains(&depth) {
+                return None;
+            }
+            Arc::new(Lookahead::new(depth))
+        }
+    };
+    Some(strategy)
+}
+
+/// Like [`lookup`], but fails with [`PokeFightError::UnknownStrategy`].
+pub fn parse(name: &str) -> Result<Arc<dyn BattleStrategy>> {
+    lookup(name).ok_or_else(|| PokeFightError::UnknownStrategy(name.to_string()))
+}
+
+/// Indices of the moves the Pokemon can pick: the one its choice item
+/// locked it into, or every move with PP left.
+fn usable_moves(pokemon: &BattlePokemon) -> Vec<usize> {
+    let moves = pokemon.get_moves();
+    match pokemon.get_locked_move() {
+        Some(index) => moves
+            .get(index)
+            .filter(|slot| slot.pp > 0)
+            .map(|_| index)
+            .into_iter()
+            .collect(),
+        None => (0..moves.len()).filter(|&i| moves[i].pp > 0).collect(),
+    }
+}
+
+/// Picks any move it can use against any opponent and never switches.
+#[derive(Debug)]
+pub struct RandomStrategy;
+
+impl BattleStrategy for RandomStrategy {
+    fn name(&self) -> String {
+        "random".to_string()
+    }
+
+    fn choose_action(&self, turn: &Turn, rng: &mut dyn RngCore) -> Action {
+        let targets: Vec<Position> = turn.opponents().into_iter().map(|(p, _)| p).collect();
+        Action::Move {
+            choice: usable_moves(turn.pokemon()).choose(rng).copied(),
+            target: *targets
+                .choose(rng)
+                .expect("an undefeated opponent has a Pokemon in battle"),
+        }
+    }
+
+    fn choose_replacement(&self, turn: &Turn, rng: &mut dyn RngCore) -> Option<usize> {
+        turn.bench().choose(rng).map(|(index, _)| *index)
+    }
+}
+
+/// Uses the move expected to deal the most damage to any opponent and never
+/// switches or sets up.
+#[derive(Debug)]
+pub struct Greedy;
+
+impl BattleStrategy for Greedy {
+    fn name(&self) -> String {
+        "greedy".to_string()
+    }
+
+    fn choose_action(&self, turn: &Turn, _rng: &mut dyn RngCore) -> Action {
+        let attacker = turn.pokemon();
+        let opponents = turn.opponents();
+        let (target, _) = opponents[0];
+        let attacks = usable_moves(attacker)
+            .into_iter()
+            .filter(|&index| attacker.get_moves()[index].known_move.is_damaging());
+        // min_by keeps the first of equally good moves
+        let best = attacks
+            .flat_map(|index| {
+                opponents
+                    .iter()
+                    .map(move |&(target, defender)| (index, target, defender))
+            })
+            .map(|(index, target, defender)| {
+                let used_move = &attacker.get_moves()[index].known_move;
+                let damage =
+                    expected_damage(attacker, defender, used_move, turn.rules(), turn.field());
+                (index, target, damage)
+            })
+            .min_by(|(_, _, a), (_, _, b)| b.total_cmp(a));
+        match best {
+            Some((index, target, _)) => Action::Move {
+                choice: Some(index),
+                target,
+            },
+            None => Action::Move {
+                choice: None,
+                target,
+            },
+        }
+    }
+}
+
+/// How much better a team member's matchup has to be before the active
+/// Pokemon switches out for it.
+const SWITCH_MARGIN: f64 = 0.5;
+
+/// Plays the type matchups: switches out of a losing matchup when a team
+/// member fares clearly better, otherwise goes after the opponent it
+/// threatens most, setting up with status and stat moves first when it
+/// cannot win quickly. The default strategy.
+#[derive(Debug)]
+pub struct TypeAware;
+
+impl BattleStrategy for TypeAware {
+    fn name(&self) -> String {
+        "type-aware".to_string()
+    }
+
+    fn choose_action(&self, turn: &Turn, _rng: &mut dyn RngCore) -> Action {
+        let (rules, field) = (turn.rules(), turn.field());
+        let active = turn.pokemon();
+        let opponents = turn.opponents();
+        let opposing: Vec<&BattlePokemon> = opponents.iter().map(|(_, o)| *o).collect();
+
+        if turn.can_switch() {
+            let current = matchup(active, &opposing, rules, field);
+            if current < 0.0
+                && let Some((index, better)) = best_switch(turn)
+                && better - current >= SWITCH_MARGIN
+            {
+                return Action::Switch(index);
+            }
+        }
+        // min_by keeps the first of equally threatened opponents
+        let (target, defender) = *opponents
+            .iter()
+            .min_by(|(_, a), (_, b)| {
+                threat(active, b, rules, field).total_cmp(&threat(active, a, rules, field))
+            })
+            .expect("an undefeated opponent has a Pokemon in battle");
+        Action::Move {
+            choice: choose_move(active, defender, rules, field),
+            target,
+        }
+    }
+}
+
+/// Deepest lookahead, in rounds. Every round multiplies the positions to
+/// look at by twice the number of attacks.
+pub const MAX_LOOKAHEAD_DEPTH: u32 = 4;
+
+/// Plans its attacks a few rounds ahead: the move and target that leave
+/// the opponents with the least HP for the HP it loses on the way, assuming
+/// every opponent hits back as hard as it can (minimax). Misses are weighed
+/// by their chance (expectimax). Before acting it considers switching to
+/// each team member on the bench, which takes the opponents' hits instead.
+///
+/// Only damage counts: status and stat moves, allies and spread damage are
+/// left out of the plan.
+#[derive(Debug)]
+pub struct Lookahead {
+    depth: u32,
+}
+
+impl Lookahead {
+    /// Looks `depth` rounds ahead, 1 to [`MAX_LOOKAHEAD_DEPTH`].
+    pub fn new(depth: u32) -> Self {
+        Self {
+            depth: depth.clamp(1, MAX_LOOKAHEAD_DEPTH),
+        }
+    }
+
+    pub fn get_depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Best expected score of the options `plan` has over `depth` rounds.
+    fn value(&self, plan: &Plan, hp: f64, opponents: &[f64], depth: u32) -> f64 {
+        if depth == 0 || hp <= 0.0 || opponents.iter().all(|&o| o <= 0.0) {
+            return plan.score(hp, opponents);
+        }
+        self.options(plan, hp, opponents, depth)
+            .into_iter()
+            .map(|(score, _, _)| score)
+            .fold(f64::MIN, f64::max)
+    }
+
+    /// Expected score of every attack `plan` has on a living opponent, as
+    /// (score, move, opponent index).
+    fn options(
+        &self,
+        plan: &Plan,
+        hp: f64,
+        opponents: &[f64],
+        depth: u32,
+    ) -> Vec<(f64, Option<usize>, usize)> {
+        let mut options = Vec::new();
+        for attack in &plan.attacks {
+            for (target, &(damage, chance)) in attack.on_opponents.iter().enumerate() {
+                if opponents[target] <= 0.0 {
+                    continue;
+                }
+                let round = |dealt| plan.round(hp, opponents, attack.priority, target, dealt);
+                let (hit_hp, hit_opponents) = round(damage);
+                let mut score = chance * self.value(plan, hit_hp, &hit_opponents, depth - 1);
+                if chance < 1.0 {
+                    let (miss_hp, miss_opponents) = round(0.0);
+                    score += (1.0 - chance) * self.value(plan, miss_hp, &miss_opponents, depth - 1);
+                }
+                options.push((score, attack.choice, target));
+            }
+        }
+        options
+    }
+}
+
+impl Default for Lookahead {
+    fn default() -> Self {
+        Self::new(2)
+    }
+}
+
+impl BattleStrategy for Lookahead {
+    fn name(&self) -> String {
+        format!("lookahead-{}", self.depth)
+    }
+
+    fn choose_action(&self, turn: &Turn, _rng: &mut dyn RngCore) -> Action {
+        let opponents = turn.opponents();
+        let hp: Vec<f64> = opponents
+            .iter()
+            .map(|(_, o)| o.get_current_hp() as f64)
+            .collect();
+
+        let plan = Plan::new(turn.pokemon(), turn);
+        let current = plan.hp;
+        let mut best = self
+            .options(&plan, current, &hp, self.depth)
+            .into_iter()
+            // min_by keeps the first of equally good options
+            .min_by(|(a, _, _), (b, _, _)| b.total_cmp(a))
+            .map(|(score, choice, target)| {
+                let target = opponents[target].0;
+                (score, Action::Move { choice, target })
+            })
+            .unwrap_or((
+                f64::MIN,
+                Action::Move {
+                    choice: None,
+                    target: opponents[0].0,
+                },
+            ));
+
+        if turn.can_switch() {
+            for (index, member) in turn.bench() {
+                let plan = Plan::new(member, turn);
+                let (after, _) = plan.round(plan.hp, &hp, 0, 0, 0.0);
+                let score = self.value(&plan, after, &hp, self.depth - 1);
+                if score > best.0 {
+                    best = (score, Action::Switch(index));
+                }
+            }
+        }
+        best.1
+    }
+}
+
+/// One attack in a plan.
+struct PlannedAttack {
+    /// Move index, `None` for struggle.
+    choice: Option<usize>,
+    priority: i64,
+    /// Damage if it hits and the chance to hit, per opponent.
+    on_opponents: Vec<(f64, f64)>,
+}
+
+/// Everything the lookahead needs to know about one of its team members in
+/// the current battle, so that no damage has to be calculated while it
+/// searches.
+struct Plan {
+    hp: f64,
+    max_hp: f64,
+    speed: i64,
+    attacks: Vec<PlannedAttack>,
+    /// Per opponent: expected damage of its best attack on the team member,
+    /// its max HP and its speed.
+    opponents: Vec<(f64, f64, i64)>,
+}
+
+impl Plan {
+    fn new(pokemon: &BattlePokemon, turn: &Turn) -> Self {
+        let (rules, field) = (turn.rules(), turn.field());
+        let opponents = turn.opponents();
+        let struggle = Move::struggle();
+        let mut choices: Vec<(Option<usize>, &Move)> = usable_moves(pokemon)
+            .into_iter()
+            .map(|index| (Some(index), &pokemon.get_moves()[index].known_move))
+            .filter(|(_, m)| m.is_damaging())
+            .collect();
+        if choices.is_empty() {
+            choices.push((None, &struggle));
+        }
+        let attacks = choices
+            .into_iter()
+            .map(|(choice, used_move)| PlannedAttack {
+                choice,
+                priority: used_move.get_priority(),
+                on_opponents: opponents
+                    .iter()
+                    .map(|(_, defender)| {
+                        let multiplier =
+                            damage_multiplier(used_move, pokemon, defender, rules, field);
+                        let damage = rules
+                            .damage_model
+                            .estimate(pokemon, defender, used_move, multiplier);
+                        let chance = hit_chance(used_move, pokemon, defender).unwrap_or(1.0);
+                        (damage, chance)
+                    })
+                    .collect(),
+            })
+            .collect();
+        Self {
+            hp: pokemon.get_current_hp() as f64,
+            max_hp: pokemon.get_max_hp().max(1) as f64,
+            speed: pokemon.get_speed(),
+            attacks,
+            opponents: opponents
+                .iter()
+                .map(|(_, opponent)| {
+                    (
+                        best_attack(opponent, pokemon, rules, field),
+                        opponent.get_max_hp().max(1) as f64,
+                        opponent.get_speed(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// HP of the team member and the opponents after a round in which it
+    /// deals `dealt` damage to the opponent at `target`. Opponents that are
+    /// faster, or as fast, hit it first; it only attacks if it survives.
+    fn round(
+        &self,
+        hp: f64,
+        opponents: &[f64],
+        priority: i64,
+        target: usize,
+        dealt: f64,
+    ) -> (f64, Vec<f64>) {
+        let mut hp = hp;
+        let mut opponents = opponents.to_vec();
+        let first = |speed: i64| (0, speed) >= (priority, self.speed);
+        for (o, &(damage, _, speed)) in self.opponents.iter().enumerate() {
+            if opponents[o] > 0.0 && first(speed) {
+                hp -= damage;
+            }
+        }
+        if hp > 0.0 {
+            opponents[target] -= dealt;
+        }
+        for (o, &(damage, _, speed)) in self.opponents.iter().enumerate() {
+            if opponents[o] > 0.0 && !first(speed) {
+                hp -= damage;
+            }
+        }
+        (hp, opponents)
+    }
+
+    /// Average share of HP the opponents are missing, minus the share of
+    /// its max HP the team member lost since the plan was made.
+    fn score(&self, hp: f64, opponents: &[f64]) -> f64 {
+        let missing: f64 = opponents
+            .iter()
+            .zip(&self.opponents)
+            .map(|(&o, &(_, max_hp, _))| 1.0 - o.max(0.0) / max_hp)
+            .sum();
+        missing / opponents.len() as f64 - (self.hp - hp.max(0.0)) / self.max_hp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::battle::field::Field;
+    use crate::models::battle::{BattleParticipant, BattleParticipants, BattleRules};
+    use crate::models::moves::DamageClass;
+    use crate::models::test_utils::{create_test_move, create_test_pokemon};
+    use crate::models::trainer::Trainer;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_lookup_strategies_by_name() {
+        assert_eq!(parse("greedy").unwrap().name(), "greedy");
+        assert_eq!(parse(" Type-Aware ").unwrap().name(), "type-aware");
+        assert_eq!(parse("lookahead").unwrap().name(), "lookahead-2");
+        assert_eq!(parse("lookahead-4").unwrap().name(), "lookahead-4");
+        for name in ["lookahead-0", "lookahead-5", "lookahead-9", "cheating"] {
+            assert!(matches!(
+                parse(name),
+                Err(PokeFightError::UnknownStrategy(unknown)) if unknown == name
+            ));
+        }
+    }
+
+    #[test]
+    fn test_strategies_play_differently() {
+        let rules = BattleRules::default();
+        let field = Field::default();
+        let mut rng = StdRng::seed_from_u64(0);
+        let special =
+            |name, power, type_name| create_test_move(name, power, type_name, DamageClass::Special);
+        let mut quick_attack = special("quick-attack", 40, "normal");
+        quick_attack.priority = 1;
+        let mut thunder = special("thunder", 110, "electric");
+        thunder.accuracy = Some(70);
+        let pikachu = create_test_pokemon("pikachu", 35, 55, 40, 90, "electric");
+        let jolteon = create_test_pokemon("jolteon", 65, 65, 60, 130, "electric");
+        let ash = Trainer::new("Ash".to_string(), pikachu.clone());
+        let gary = Trainer::new("Gary".to_string(), jolteon.clone());
+
+        // Both are one hit away from fainting, and Jolteon is faster
+        let mut participant = BattleParticipant::new(&ash, &rules.movedex, 1);
+        participant.team[0] = BattlePokemon::new(&pikachu).with_moves(vec![
+            quick_attack,
+            special("thunderbolt", 90, "electric"),
+            thunder,
+        ]);
+        participant.team[0].take_damage(100);
+        let mut opponent = BattleParticipant::new(&gary, &rules.movedex, 1);
+        opponent.team[0] =
+            BattlePokemon::new(&jolteon).with_moves(vec![special("swift", 60, "normal")]);
+        opponent.team[0].take_damage(135);
+        let participants = BattleParticipants {
+            sides: [participant, opponent],
+        };
+        let turn = Turn::new(
+            &participants,
+            Position { side: 0, slot: 0 },
+            &[],
+            &rules,
+            &field,
+        );
+        let mut choice =
+            |strategy: &dyn BattleStrategy| match strategy.choose_action(&turn, &mut rng) {
+                Action::Move { choice, .. } => choice,
+                Action::Switch(_) => panic!("nobody to switch to"),
+            };
+
+        // Greedy goes for the most damage, which Thunder's accuracy keeps
+        // below Thunderbolt's
+        assert_eq!(choice(&Greedy), Some(1));
+        // Only Quick Attack lands before Jolteon does
+        assert_eq!(choice(&Lookahead::new(1)), Some(0));
+        assert_eq!(choice(&Lookahead::new(3)), Some(0));
+        // Random plays every move sooner or later
+        let mut picked = [false; 3];
+        for _ in 0..50 {
+            picked[choice(&RandomStrategy).unwrap()] = true;
+        }
+        assert_eq!(picked, [true; 3]);
+    }
+}
@@ -0,0 +1,380 @@
+use std::str::FromStr;
+
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use super::moves::{DamageClass, Move};
+use super::pokemon::BattlePokemon;
+
+/// Move power at which the classic formula deals its original damage.
+const CLASSIC_POWER: f64 = 60.0;
+
+/// Outcome of a single damage calculation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DamageRoll {
+    pub damage: i64,
+    pub critical: bool,
+}
+
+/// Turns an attack into a number of hit points.
+///
+/// `type_multiplier` already combines type effectiveness, STAB, held item
+/// boosts and weather and terrain of `used_move`.
+pub trait DamageModel: Send + Sync {
+    fn calculate(
+        &self,
+        attacker: &BattlePokemon,
+        defender: &BattlePokemon,
+        used_move: &Move,
+        type_multiplier: f64,
+        rng: &mut dyn RngCore,
+    ) -> DamageRoll;
+
+    /// Typical damage of `used_move` without luck, used to plan moves.
+    fn estimate(
+        &self,
+        attacker: &BattlePokemon,
+        defender: &BattlePokemon,
+        used_move: &Move,
+        type_multiplier: f64,
+    ) -> f64;
+}
+
+/// The original PokeFight formula: attack/defense ratio times 20, scaled by
+/// the move's power relative to a 60 power move.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClassicDamage;
+
+impl DamageModel for ClassicDamage {
+    fn calculate(
+        &self,
+        attacker: &BattlePokemon,
+        defender: &BattlePokemon,
+        used_move: &Move,
+        type_multiplier: f64,
+        _rng: &mut dyn RngCore,
+    ) -> DamageRoll {
+        DamageRoll {
+            damage: minimum_damage(self.estimate(attacker, defender, used_move, type_multiplier)),
+            critical: false,
+        }
+    }
+
+    fn estimate(
+        &self,
+        attacker: &BattlePokemon,
+        defender: &BattlePokemon,
+        used_move: &Move,
+        type_multiplier: f64,
+    ) -> f64 {
+        let ratio = attack_ratio(attacker, defender, used_move);
+        let power = used_move.get_power() as f64 / CLASSIC_POWER;
+        ratio * 20.0 * power * type_multiplier
+    }
+}
+
+/// The damage formula of the main-series games:
+///
+/// `((2 * level / 5 + 2) * power * attack / defense / 50 + 2) * modifiers`
+///
+/// where the level is the attacker's and the modifiers are critical hits,
+/// a random spread of 85-100% and the type multiplier.
+#[derive(Debug, Clone, Copy)]
+pub struct MainSeriesDamage {
+    /// Probability of a critical hit, between 0 and 1.
+    pub critical_chance: f64,
+    pub critical_multiplier: f64,
+    /// Scale damage by a random factor between 0.85 and 1.0.
+    pub random_spread: bool,
+}
+
+impl Default for MainSeriesDamage {
+    fn default() -> Self {
+        Self {
+            critical_chance: 1.0 / 24.0,
+            critical_multiplier: 1.5,
+            random_spread: true,
+        }
+    }
+}
+
+impl MainSeriesDamage {
+    /// Damage before any modifiers.
+    fn base_damage(
+        &self,
+        attacker: &BattlePokemon,
+        defender: &BattlePokemon,
+        used_move: &Move,
+    ) -> f64 {
+        let level_factor = 2.0 * attacker.get_level() as f64 / 5.0 + 2.0;
+        let ratio = attack_ratio(attacker, defender, used_move);
+        level_factor * used_move.get_power() as f64 * ratio / 50.0 + 2.0
+    }
+}
+
+impl DamageModel for MainSeriesDamage {
+    fn calculate(
+        &self,
+        attacker: &BattlePokemon,
+        defender: &BattlePokemon,
+        used_move: &Move,
+        type_multiplier: f64,
+        rng: &mut dyn RngCore,
+    ) -> DamageRoll {
+        let base = self.base_damage(attacker, defender, used_move);
+
+        let critical = rng.random_bool(self.critical_chance.clamp(0.0, 1.0));
+        let critical_factor = if critical {
+            self.critical_multiplier
+        } else {
+            1.0
+        };
+        let spread = if self.random_spread {
+            rng.random_range(0.85..=1.0)
+        } else {
+            1.0
+        };
+
+        DamageRoll {
+            damage: minimum_damage(base * critical_factor * spread * type_multiplier),
+            critical,
+        }
+    }
+
+    fn estimate(
+        &self,
+        attacker: &BattlePokemon,
+        defender: &BattlePokemon,
+        used_move: &Move,
+        type_multiplier: f64,
+    ) -> f64 {
+        let spread = if self.random_spread { 0.925 } else { 1.0 };
+        self.base_damage(attacker, defender, used_move) * spread * type_multiplier
+    }
+}
+
+/// Selects a damage model by name, e.g. from a CLI flag or a web request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Ruleset {
+    Classic,
+    #[default]
+    MainSeries,
+}
+
+impl Ruleset {
+    pub fn damage_model(&self) -> Box<dyn DamageModel> {
+        match self {
+            Ruleset::Classic => Box::new(ClassicDamage),
+            Ruleset::MainSeries => Box::new(MainSeriesDamage::default()),
+        }
+    }
+}
+
+impl FromStr for Ruleset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "classic" => Ok(Ruleset::Classic),
+            "main-series" => Ok(Ruleset::MainSeries),
+            other => Err(format!(
+                "unknown ruleset '{}', expected 'classic' or 'main-series'",
+                other
+            )),
+        }
+    }
+}
+
+/// Attack stat over defense stat for `used_move`.
+///
+/// Physical moves use attack against defense, special moves special attack
+/// against special defense. A Pokemon without a move set has no damage class
+/// to go by, so it attacks with the higher of its two attack stats. Both
+/// stats include the modifiers of the Pokemon's abilities.
+pub fn attack_ratio(attacker: &BattlePokemon, defender: &BattlePokemon, used_move: &Move) -> f64 {
+    let class = match used_move.get_damage_class() {
+        DamageClass::Status => None,
+        _ if attacker.get_moves().is_empty() => None,
+        class => Some(class),
+    };
+    let special = class.map_or(
+        attacker.get_special_attack() > attacker.get_attack(),
+        |class| class == DamageClass::Special,
+    );
+    let (attack, defense) = if special {
+        (
+            ("special-attack", attacker.get_special_attack()),
+            ("special-defense", defender.get_special_defense()),
+        )
+    } else {
+        (
+            ("attack", attacker.get_attack()),
+            ("defense", defender.get_defense()),
+        )
+    };
+    let attack = attack.1 as f64 * attacker.get_ability_modifier(attack.0, used_move);
+    let defense = defense.1.max(1) as f64 * defender.get_ability_modifier(defense.0, used_move);
+    attack / defense
+}
+
+// Ensure minimum damage of 1 to prevent infinite battles, unless the
+// defender is immune
+fn minimum_damage(damage: f64) -> i64 {
+    if damage <= 0.0 {
+        return 0;
+    }
+    (damage.floor() as i64).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::pokemon::{Pokemon, Stat, StatName};
+    use crate::models::test_utils::{create_test_move, create_test_pokemon};
+
+    fn sixty_power() -> Move {
+        create_test_move("headbutt", 60, "normal", DamageClass::Physical)
+    }
+
+    fn without_luck() -> MainSeriesDamage {
+        MainSeriesDamage {
+            critical_chance: 0.0,
+            random_spread: false,
+            ..MainSeriesDamage::default()
+        }
+    }
+
+    #[test]
+    fn test_weaker_attacker_still_deals_real_damage() {
+        let weak = create_test_pokemon("weak", 50, 40, 50, 50, "normal");
+        let tank = create_test_pokemon("tank", 50, 50, 120, 50, "normal");
+        let attacker = BattlePokemon::new(&weak).with_moves(vec![sixty_power()]);
+        let defender = BattlePokemon::new(&tank);
+
+        let roll =
+            without_luck().calculate(&attacker, &defender, &sixty_power(), 1.0, &mut rand::rng());
+
+        // Level 50 stats are base + 20: (22 * 60 * 60 / 140) / 50 + 2 = 13.3
+        assert_eq!(roll.damage, 13);
+        assert!(!roll.critical);
+    }
+
+    #[test]
+    fn test_immunities_deal_no_damage() {
+        let weak = create_test_pokemon("weak", 50, 40, 50, 50, "normal");
+        let tank = create_test_pokemon("tank", 50, 50, 120, 50, "ghost");
+        let attacker = BattlePokemon::new(&weak).with_moves(vec![sixty_power()]);
+        let defender = BattlePokemon::new(&tank);
+        let models: [&dyn DamageModel; 2] = [&without_luck(), &ClassicDamage];
+
+        for model in models {
+            let immune =
+                model.calculate(&attacker, &defender, &sixty_power(), 0.0, &mut rand::rng());
+            assert_eq!(immune.damage, 0);
+
+            // Resisted hits still chip away at least 1 HP
+            let resisted =
+                model.calculate(&attacker, &defender, &sixty_power(), 0.01, &mut rand::rng());
+            assert_eq!(resisted.damage, 1);
+        }
+    }
+
+    #[test]
+    fn test_critical_hits_and_spread() {
+        let mon = create_test_pokemon("mon", 50, 50, 50, 50, "normal");
+        let attacker = BattlePokemon::new(&mon).with_moves(vec![sixty_power()]);
+        let defender = BattlePokemon::new(&mon);
+
+        let always_critical = MainSeriesDamage {
+            critical_chance: 1.0,
+            ..without_luck()
+        };
+        let roll =
+            always_critical.calculate(&attacker, &defender, &sixty_power(), 1.0, &mut rand::rng());
+        assert!(roll.critical);
+        // (22 * 60 / 50 + 2) * 1.5 = 42.6
+        assert_eq!(roll.damage, 42);
+
+        let spread = MainSeriesDamage {
+            random_spread: true,
+            ..without_luck()
+        };
+        for _ in 0..100 {
+            let roll =
+                spread.calculate(&attacker, &defender, &sixty_power(), 1.0, &mut rand::rng());
+            assert!(
+                (24..=28).contains(&roll.damage),
+                "{} out of range",
+                roll.damage
+            );
+        }
+    }
+
+    fn with_special_stats(
+        mut pokemon: Pokemon,
+        special_attack: i64,
+        special_defense: i64,
+    ) -> Pokemon {
+        for (name, base_stat) in [
+            ("special-attack", special_attack),
+            ("special-defense", special_defense),
+        ] {
+            pokemon.stats.push(Stat {
+                base_stat,
+                stat: StatName {
+                    name: name.to_string(),
+                },
+            });
+        }
+        pokemon
+    }
+
+    #[test]
+    fn test_damage_class_picks_stats() {
+        let alakazam = with_special_stats(
+            create_test_pokemon("alakazam", 55, 50, 45, 120, "psychic"),
+            135,
+            95,
+        );
+        let golem = with_special_stats(
+            create_test_pokemon("golem", 80, 120, 130, 45, "rock"),
+            55,
+            65,
+        );
+        let psychic = create_test_move("psychic", 90, "psychic", DamageClass::Special);
+        let tackle = create_test_move("tackle", 40, "normal", DamageClass::Physical);
+
+        let attacker = BattlePokemon::new(&alakazam).with_moves(vec![psychic.clone()]);
+        let defender = BattlePokemon::new(&golem);
+        // Level 50 stats are base + 20
+        assert_eq!(attack_ratio(&attacker, &defender, &psychic), 155.0 / 85.0);
+        assert_eq!(attack_ratio(&attacker, &defender, &tackle), 70.0 / 150.0);
+
+        // Without a move set the stronger attack stat is used
+        let unarmed = BattlePokemon::new(&alakazam);
+        assert_eq!(
+            attack_ratio(&unarmed, &defender, &Move::struggle()),
+            155.0 / 85.0
+        );
+        let unarmed = BattlePokemon::new(&golem);
+        let target = BattlePokemon::new(&alakazam);
+        assert_eq!(
+            attack_ratio(&unarmed, &target, &Move::struggle()),
+            140.0 / 65.0
+        );
+    }
+
+    #[test]
+    fn test_classic_uses_float_ratio() {
+        let weak = create_test_pokemon("weak", 50, 40, 50, 50, "normal");
+        let tank = create_test_pokemon("tank", 50, 50, 80, 50, "normal");
+        let attacker = BattlePokemon::new(&weak).with_moves(vec![sixty_power()]);
+        let defender = BattlePokemon::new(&tank);
+
+        let roll =
+            ClassicDamage.calculate(&attacker, &defender, &sixty_power(), 2.0, &mut rand::rng());
+        // 60 / 100 * 20 * 2
+        assert_eq!(roll.damage, 24);
+    }
+}
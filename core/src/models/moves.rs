@@ -0,0 +1,455 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::pokemon::{Pokemon, StatName, TypeInfo};
+use super::status::StatusCondition;
+use crate::errors::{PokeFightError, Result};
+
+/// Most Gen 1 moves plus a few later ones, so that each type has attacks.
+///
+/// Moves whose drawbacks are not simulated (recoil, crash damage, recharging,
+/// charging turns, needing a sleeping target, fainting the user) are left
+/// out, otherwise they would always win the move selection.
+const STANDARD_MOVES: &str = include_str!("../../data/moves.json");
+
+/// How many moves a Pokemon brings into battle.
+pub const MOVE_SET_SIZE: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DamageClass {
+    Physical,
+    Special,
+    Status,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DamageClassInfo {
+    #[serde(rename = "name")]
+    pub name: DamageClass,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AilmentInfo {
+    #[serde(rename = "name")]
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveTargetInfo {
+    #[serde(rename = "name")]
+    pub name: String,
+}
+
+/// Who a move is aimed at in battle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Targeting {
+    /// One Pokemon picked by the user, like Tackle.
+    Single,
+    /// Every opponent, like Rock Slide or Growl.
+    AllOpponents,
+    /// Every other Pokemon in battle including allies, like Earthquake.
+    AllOthers,
+    /// The user's partner in a double battle.
+    Ally,
+    /// Nobody but the user or the field, like Swords Dance or Haze.
+    User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveCategoryInfo {
+    #[serde(rename = "name")]
+    pub name: String,
+}
+
+/// A stat stage change, e.g. +2 attack for Swords Dance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatChange {
+    #[serde(rename = "change")]
+    pub change: i8,
+
+    #[serde(rename = "stat")]
+    pub stat: StatName,
+}
+
+/// Secondary effects of a move.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveMeta {
+    #[serde(rename = "ailment", default)]
+    pub ailment: AilmentInfo,
+
+    /// Percent chance of inflicting the ailment. PokeAPI reports 0 for status
+    /// moves, whose ailment is their whole point and always applies.
+    #[serde(rename = "ailment_chance", default)]
+    pub ailment_chance: i64,
+
+    /// Percent chance of the stat changes, 0 if they always apply.
+    #[serde(rename = "stat_chance", default)]
+    pub stat_chance: i64,
+
+    /// E.g. `damage+raise` for attacks that boost the user's stats.
+    #[serde(rename = "category", default)]
+    pub category: Option<MoveCategoryInfo>,
+}
+
+/// A move as returned by PokeAPI's `/move/{name}` endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Move {
+    #[serde(rename = "name")]
+    pub name: String,
+
+    /// `None` for status moves and moves with fixed damage.
+    #[serde(rename = "power")]
+    pub power: Option<i64>,
+
+    /// `None` for moves that never miss.
+    #[serde(rename = "accuracy")]
+    pub accuracy: Option<i64>,
+
+    #[serde(rename = "pp")]
+    pub pp: i64,
+
+    /// Moves with higher priority go first, regardless of speed.
+    #[serde(rename = "priority", default)]
+    pub priority: i64,
+
+    #[serde(rename = "type")]
+    pub type_info: TypeInfo,
+
+    #[serde(rename = "damage_class")]
+    pub damage_class: DamageClassInfo,
+
+    /// Who the move affects, e.g. `user` or `selected-pokemon`.
+    #[serde(rename = "target", default)]
+    pub target: Option<MoveTargetInfo>,
+
+    #[serde(rename = "stat_changes", default)]
+    pub stat_changes: Vec<StatChange>,
+
+    #[serde(rename = "meta", default)]
+    pub meta: Option<MoveMeta>,
+}
+
+impl Move {
+    /// Used when every other move is out of PP. Struggle has no type, so it
+    /// hits everything for neutral damage.
+    pub fn struggle() -> Self {
+        Self {
+            name: "struggle".to_string(),
+            power: Some(50),
+            accuracy: None,
+            pp: 1,
+            priority: 0,
+            type_info: TypeInfo {
+                name: "typeless".to_string(),
+            },
+            damage_class: DamageClassInfo {
+                name: DamageClass::Physical,
+            },
+            target: None,
+            stat_changes: Vec::new(),
+            meta: None,
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_struggle(&self) -> bool {
+        self.name == "struggle"
+    }
+
+    pub fn get_type(&self) -> &str {
+        &self.type_info.name
+    }
+
+    pub fn get_power(&self) -> i64 {
+        self.power.unwrap_or(0)
+    }
+
+    pub fn get_priority(&self) -> i64 {
+        self.priority
+    }
+
+    pub fn get_damage_class(&self) -> DamageClass {
+        self.damage_class.name
+    }
+
+    /// Whether the battle engine can use this move to deal damage.
+    pub fn is_damaging(&self) -> bool {
+        self.get_damage_class() != DamageClass::Status && self.get_power() > 0
+    }
+
+    /// The status condition this move inflicts and the chance it does so.
+    pub fn get_ailment(&self) -> Option<(StatusCondition, f64)> {
+        let meta = self.meta.as_ref()?;
+        let status = StatusCondition::from_ailment(&meta.ailment.name)?;
+        let chance = match meta.ailment_chance {
+            0 => 1.0,
+            percent => percent as f64 / 100.0,
+        };
+        Some((status, chance))
+    }
+
+    /// Status moves that put a condition on the target, like Thunder Wave.
+    pub fn is_status_inflicting(&self) -> bool {
+        self.get_damage_class() == DamageClass::Status && self.get_ailment().is_some()
+    }
+
+    pub fn get_stat_changes(&self) -> &[StatChange] {
+        &self.stat_changes
+    }
+
+    /// Chance that the stat changes apply when the move hits.
+    pub fn get_stat_chance(&self) -> f64 {
+        match self.meta.as_ref().map_or(0, |meta| meta.stat_chance) {
+            0 => 1.0,
+            percent => percent as f64 / 100.0,
+        }
+    }
+
+    /// Whether the stat changes go to the user, like Swords Dance or Metal
+    /// Claw, rather than to the target, like Growl or Psychic.
+    pub fn changes_own_stats(&self) -> bool {
+        self.target.as_ref().is_some_and(|t| t.name == "user")
+            || self
+                .meta
+                .as_ref()
+                .and_then(|meta| meta.category.as_ref())
+                .is_some_and(|c| c.name == "damage+raise")
+    }
+
+    /// Who the move is aimed at. Moves without a target hit one Pokemon.
+    pub fn get_targeting(&self) -> Targeting {
+        match self.target.as_ref().map(|t| t.name.as_str()) {
+            Some("all-opponents") => Targeting::AllOpponents,
+            Some("all-other-pokemon") => Targeting::AllOthers,
+            Some("ally") => Targeting::Ally,
+            Some("user" | "users-field" | "opponents-field" | "entire-field") => Targeting::User,
+            _ => Targeting::Single,
+        }
+    }
+
+    /// Status moves that only change stat stages, like Growl.
+    pub fn is_stat_changing(&self) -> bool {
+        self.get_damage_class() == DamageClass::Status && !self.stat_changes.is_empty()
+    }
+}
+
+/// A move known by a Pokemon in battle, with the PP it has left.
+#[derive(Debug, Clone)]
+pub struct MoveSlot {
+    pub known_move: Move,
+    pub pp: i64,
+}
+
+impl MoveSlot {
+    pub fn new(known_move: Move) -> Self {
+        let pp = known_move.pp;
+        Self { known_move, pp }
+    }
+}
+
+/// Move data by name, used to turn a Pokemon's learnset into a move set.
+#[derive(Debug, Clone)]
+pub struct Movedex {
+    moves: HashMap<String, Move>,
+}
+
+impl Movedex {
+    pub fn standard() -> Self {
+        Self::from_json(STANDARD_MOVES).expect("bundled moves should be valid")
+    }
+
+    /// Parses a JSON array of moves in PokeAPI format.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let moves: Vec<Move> = serde_json::from_str(json)
+            .map_err(|e| PokeFightError::ConfigError(format!("Invalid moves: {}", e)))?;
+        Ok(Self {
+            moves: moves.into_iter().map(|m| (m.name.clone(), m)).collect(),
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Move> {
+        self.moves.get(name)
+    }
+
+    /// Picks up to [`MOVE_SET_SIZE`] moves for `pokemon`: one support move
+    /// it knows, if any, and damaging moves. Status inflicting moves are
+    /// preferred as support, then the biggest stat changes.
+    ///
+    /// Moves come from the Pokemon's learnset. Pokemon without a known
+    /// learnset (e.g. from the bundled Pokedex) get moves of their own types
+    /// and normal attacks instead. Damaging moves are ranked by power,
+    /// accuracy, STAB and the attack stat their damage class uses. The
+    /// strongest move of each type is taken first for coverage, the
+    /// remaining slots go to the strongest leftovers.
+    pub fn move_set(&self, pokemon: &Pokemon) -> Vec<Move> {
+        let types = pokemon.get_types();
+        let learnset: Vec<&Move> = pokemon
+            .get_move_names()
+            .into_iter()
+            .filter_map(|name| self.get(name))
+            .collect();
+        let (mut candidates, mut support): (Vec<&Move>, Vec<&Move>) = if learnset
+            .iter()
+            .any(|m| m.is_damaging())
+        {
+            (
+                learnset
+                    .iter()
+                    .filter(|m| m.is_damaging())
+                    .copied()
+                    .collect(),
+                learnset
+                    .iter()
+                    .filter(|m| m.is_status_inflicting() || m.is_stat_changing())
+                    .copied()
+                    .collect(),
+            )
+        } else {
+            let own_type = |m: &Move| types.contains(&m.get_type());
+            (
+                self.moves
+                    .values()
+                    .filter(|m| m.is_damaging() && (m.get_type() == "normal" || own_type(m)))
+                    .collect(),
+                self.moves
+                    .values()
+                    .filter(|m| (m.is_status_inflicting() || m.is_stat_changing()) && own_type(m))
+                    .collect(),
+            )
+        };
+
+        // Conditions last longer than stat changes, bigger changes go first
+        let magnitude = |m: &Move| -> i64 {
+            m.get_stat_changes()
+                .iter()
+                .map(|c| i64::from(c.change).abs())
+                .sum()
+        };
+        support.sort_by(|a, b| {
+            b.is_status_inflicting()
+                .cmp(&a.is_status_inflicting())
+                .then_with(|| magnitude(b).cmp(&magnitude(a)))
+                .then_with(|| b.accuracy.unwrap_or(100).cmp(&a.accuracy.unwrap_or(100)))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        let mut chosen: Vec<&Move> = support.into_iter().take(1).collect();
+
+        let score = |m: &Move| {
+            let attack = match m.get_damage_class() {
+                DamageClass::Special => pokemon.get_special_attack(),
+                _ => pokemon.get_attack(),
+            };
+            let accuracy = m.accuracy.unwrap_or(100) as f64 / 100.0;
+            let stab = if types.contains(&m.get_type()) {
+                1.5
+            } else {
+                1.0
+            };
+            m.get_power() as f64 * attack as f64 * accuracy * stab
+        };
+        candidates.sort_by(|a, b| {
+            score(b)
+                .total_cmp(&score(a))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        for m in &candidates {
+            if chosen.len() < MOVE_SET_SIZE
+                && chosen
+                    .iter()
+                    .all(|c| !c.is_damaging() || c.get_type() != m.get_type())
+            {
+                chosen.push(m);
+            }
+        }
+        for m in &candidates {
+            if chosen.len() < MOVE_SET_SIZE && chosen.iter().all(|c| c.name != m.name) {
+                chosen.push(m);
+            }
+        }
+        chosen.into_iter().cloned().collect()
+    }
+}
+
+impl Default for Movedex {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_utils::create_test_pokemon;
+
+    #[test]
+    fn test_standard_movedex() {
+        let movedex = Movedex::standard();
+
+        let thunderbolt = movedex.get("thunderbolt").unwrap();
+        assert_eq!(thunderbolt.get_type(), "electric");
+        assert_eq!(thunderbolt.get_power(), 90);
+        assert_eq!(thunderbolt.get_damage_class(), DamageClass::Special);
+        assert!(!movedex.get("thunder-wave").unwrap().is_damaging());
+        assert_eq!(thunderbolt.get_targeting(), Targeting::Single);
+        assert_eq!(
+            movedex.get("earthquake").unwrap().get_targeting(),
+            Targeting::AllOthers
+        );
+        assert_eq!(
+            movedex.get("growl").unwrap().get_targeting(),
+            Targeting::AllOpponents
+        );
+    }
+
+    #[test]
+    fn test_move_set_from_learnset() {
+        let movedex = Movedex::standard();
+        let pokemon: Pokemon = serde_json::from_value(serde_json::json!({
+            "id": 25,
+            "name": "pikachu",
+            "stats": [],
+            "types": [{ "type": { "name": "electric" } }],
+            "moves": [
+                { "move": { "name": "thunder-shock" } },
+                { "move": { "name": "thunderbolt" } },
+                { "move": { "name": "growl" } },
+                { "move": { "name": "quick-attack" } },
+                { "move": { "name": "brick-break" } }
+            ]
+        }))
+        .unwrap();
+
+        let names: Vec<String> = movedex
+            .move_set(&pokemon)
+            .into_iter()
+            .map(|m| m.name)
+            .collect();
+
+        // Growl is the only support move, brick-break is not in the movedex
+        assert_eq!(
+            names,
+            ["growl", "thunderbolt", "quick-attack", "thunder-shock"]
+        );
+    }
+
+    #[test]
+    fn test_move_set_without_learnset_uses_own_types() {
+        let movedex = Movedex::standard();
+        let charmander = create_test_pokemon("charmander", 39, 52, 43, 65, "fire");
+
+        let moves = movedex.move_set(&charmander);
+
+        assert_eq!(moves.len(), MOVE_SET_SIZE);
+        assert_eq!(moves[0].get_type(), "fire");
+        assert_eq!(moves[1].get_type(), "normal");
+        assert!(moves
+            .iter()
+            .all(|m| m.get_type() == "fire" || m.get_type() == "normal"));
+    }
+}
@@ -1,7 +1,22 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rand::{Rng, RngCore};
 use serde::{Deserialize, Serialize};
 
+use super::battle::abilities::{self, Ability};
+use super::battle::items::HeldItem;
+use super::moves::{Move, MoveSlot};
+use super::stages::{stat_multiplier, StatStages};
+use super::stats::{PokemonBuild, StatValues};
+use super::status::{StatusCondition, PARALYSIS_SKIP_CHANCE, SLEEP_TURNS, THAW_CHANCE};
+use crate::errors::PokeFightError;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Pokemon {
+    #[serde(rename = "id", default)]
+    pub id: u32,
+
     #[serde(rename = "name")]
     pub name: String,
 
@@ -10,6 +25,13 @@ pub struct Pokemon {
 
     #[serde(rename = "types")]
     pub types: Vec<Type>,
+
+    /// Names of the moves this Pokemon can learn.
+    #[serde(rename = "moves", default)]
+    pub moves: Vec<PokemonMove>,
+
+    #[serde(rename = "abilities", default)]
+    pub abilities: Vec<PokemonAbility>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -39,6 +61,37 @@ pub struct TypeInfo {
     pub name: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PokemonMove {
+    #[serde(rename = "move")]
+    pub move_info: MoveInfo,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MoveInfo {
+    #[serde(rename = "name")]
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PokemonAbility {
+    #[serde(rename = "ability")]
+    pub ability: AbilityInfo,
+
+    /// Hidden abilities are only found on special encounters.
+    #[serde(rename = "is_hidden", default)]
+    pub is_hidden: bool,
+
+    #[serde(rename = "slot", default)]
+    pub slot: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AbilityInfo {
+    #[serde(rename = "name")]
+    pub name: String,
+}
+
 // Helper methods
 impl Pokemon {
     pub fn get_name(&self) -> &str {
@@ -52,6 +105,16 @@ impl Pokemon {
             .unwrap_or("normal")
     }
 
+    pub fn get_types(&self) -> Vec<&str> {
+        if self.types.is_empty() {
+            return vec!["normal"];
+        }
+        self.types
+            .iter()
+            .map(|t| t.type_info.name.as_str())
+            .collect()
+    }
+
     pub fn get_hp(&self) -> i64 {
         self.stats
             .iter()
@@ -76,6 +139,22 @@ impl Pokemon {
             .unwrap_or(50)
     }
 
+    pub fn get_special_attack(&self) -> i64 {
+        self.stats
+            .iter()
+            .find(|s| s.stat.name == "special-attack")
+            .map(|s| s.base_stat)
+            .unwrap_or(50)
+    }
+
+    pub fn get_special_defense(&self) -> i64 {
+        self.stats
+            .iter()
+            .find(|s| s.stat.name == "special-defense")
+            .map(|s| s.base_stat)
+            .unwrap_or(50)
+    }
+
     pub fn get_speed(&self) -> i64 {
         self.stats
             .iter()
@@ -83,25 +162,214 @@ impl Pokemon {
             .map(|s| s.base_stat)
             .unwrap_or(50)
     }
+
+    pub fn get_base_stat_total(&self) -> i64 {
+        self.stats.iter().map(|s| s.base_stat).sum()
+    }
+
+    /// Names of the regular abilities, leaving out the hidden one.
+    pub fn get_ability_names(&self) -> Vec<&str> {
+        self.abilities
+            .iter()
+            .filter(|a| !a.is_hidden)
+            .map(|a| a.ability.name.as_str())
+            .collect()
+    }
+
+    pub fn get_move_names(&self) -> Vec<&str> {
+        self.moves
+            .iter()
+            .map(|m| m.move_info.name.as_str())
+            .collect()
+    }
+}
+
+/// Which Pokemon a trainer brings: a random draw or a specific species.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(try_from = "ChoiceInput", into = "String")]
+pub enum PokemonChoice {
+    #[default]
+    Random,
+    Id(u32),
+    Name(String),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ChoiceInput {
+    Id(u32),
+    Text(String),
+}
+
+impl TryFrom<ChoiceInput> for PokemonChoice {
+    type Error = PokeFightError;
+
+    fn try_from(input: ChoiceInput) -> Result<Self, Self::Error> {
+        match input {
+            ChoiceInput::Id(id) => Ok(PokemonChoice::Id(id)),
+            ChoiceInput::Text(text) => text.parse(),
+        }
+    }
+}
+
+impl FromStr for PokemonChoice {
+    type Err = PokeFightError;
+
+    /// Accepts a Pokedex number (`25`), a name (`Mr. Mime`) or `random`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(PokeFightError::ConfigError(
+                "Pokemon pick is empty, expected a name, a Pokedex number or `random`".to_string(),
+            ));
+        }
+        if s.eq_ignore_ascii_case("random") {
+            return Ok(PokemonChoice::Random);
+        }
+        if let Ok(id) = s.parse::<u32>() {
+            return Ok(PokemonChoice::Id(id));
+        }
+        // PokeAPI names are lowercase and hyphenated, e.g. "mr-mime"
+        let name = s
+            .to_lowercase()
+            .replace(['.', '\''], "")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join("-");
+        Ok(PokemonChoice::Name(name))
+    }
+}
+
+impl fmt::Display for PokemonChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PokemonChoice::Random => write!(f, "random"),
+            PokemonChoice::Id(id) => write!(f, "{}", id),
+            PokemonChoice::Name(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl From<PokemonChoice> for String {
+    fn from(choice: PokemonChoice) -> Self {
+        choice.to_string()
+    }
 }
 
 #[derive(Debug)]
 pub struct BattlePokemon<'a> {
     pokemon: &'a Pokemon,
+    level: u8,
+    stats: StatValues<i64>,
     current_hp: i64,
     max_hp: i64,
+    moves: Vec<MoveSlot>,
+    stages: StatStages,
+    ability: Option<&'static dyn Ability>,
+    item: Option<&'static dyn HeldItem>,
+    /// Move a choice item locked the Pokemon into.
+    locked_move: Option<usize>,
+    status: Option<StatusCondition>,
+    sleep_turns: u8,
 }
 
 impl<'a> BattlePokemon<'a> {
+    /// Battles with the [`PokemonBuild`] defaults: level 50, perfect IVs,
+    /// no EVs and a neutral nature. Its ability is the first regular one the
+    /// battle engine implements.
     pub fn new(pokemon: &'a Pokemon) -> Self {
-        let max_hp = pokemon.get_hp() * 2;
+        let build = PokemonBuild::default();
+        let stats = build.stats(pokemon);
+        let max_hp = stats.hp;
+        let ability = pokemon
+            .get_ability_names()
+            .into_iter()
+            .find_map(abilities::lookup);
         Self {
             pokemon,
+            level: build.level,
+            stats,
             max_hp,
             current_hp: max_hp,
+            moves: Vec::new(),
+            stages: StatStages::default(),
+            ability,
+            item: None,
+            locked_move: None,
+            status: None,
+            sleep_turns: 0,
+        }
+    }
+
+    /// Recalculates stats and HP from `build`.
+    pub fn with_build(mut self, build: PokemonBuild) -> Self {
+        self.level = build.level;
+        self.stats = build.stats(self.pokemon);
+        self.max_hp = self.stats.hp;
+        self.current_hp = self.max_hp;
+        self
+    }
+
+    pub fn with_ability(mut self, ability: Option<&'static dyn Ability>) -> Self {
+        self.ability = ability;
+        self
+    }
+
+    pub fn with_item(mut self, item: Option<&'static dyn HeldItem>) -> Self {
+        self.item = item;
+        self
+    }
+
+    pub fn with_moves(mut self, moves: Vec<Move>) -> Self {
+        self.moves = moves.into_iter().map(MoveSlot::new).collect();
+        self
+    }
+
+    pub fn get_moves(&self) -> &[MoveSlot] {
+        &self.moves
+    }
+
+    pub fn get_stages(&self) -> &StatStages {
+        &self.stages
+    }
+
+    pub fn get_stages_mut(&mut self) -> &mut StatStages {
+        &mut self.stages
+    }
+
+    /// Leaving the battle resets stat stages and a choice lock. Status
+    /// conditions stay.
+    pub fn switch_out(&mut self) {
+        self.stages = StatStages::default();
+        self.locked_move = None;
+    }
+
+    /// Spends one PP of the move at `index`, falling back to Struggle when
+    /// there is no such move or it is out of PP. A choice item locks the
+    /// Pokemon into the move.
+    pub fn use_move(&mut self, index: Option<usize>) -> Move {
+        match index.and_then(|i| self.moves.get_mut(i)) {
+            Some(slot) if slot.pp > 0 => {
+                slot.pp -= 1;
+                if self.item.is_some_and(|item| item.locks_move()) {
+                    self.locked_move = index;
+                }
+                slot.known_move.clone()
+            }
+            _ => Move::struggle(),
         }
     }
 
+    /// Restores up to `amount` HP, returning how much was restored.
+    pub fn heal(&mut self, amount: i64) -> i64 {
+        if self.is_fainted() {
+            return 0;
+        }
+        let healed = amount.clamp(0, self.max_hp - self.current_hp);
+        self.current_hp += healed;
+        healed
+    }
+
     pub fn take_damage(&mut self, damage: i64) {
         if self.current_hp - damage <= 0 {
             self.current_hp = 0;
@@ -114,21 +382,225 @@ impl<'a> BattlePokemon<'a> {
         self.current_hp == 0
     }
 
+    pub fn get_ability(&self) -> Option<&'static dyn Ability> {
+        self.ability
+    }
+
+    /// Multiplier the ability puts on `stat` while `used_move` is used by or
+    /// against this Pokemon.
+    pub fn get_ability_modifier(&self, stat: &str, used_move: &Move) -> f64 {
+        self.ability
+            .map_or(1.0, |ability| ability.stat_modifier(self, stat, used_move))
+    }
+
+    /// Whether the ability makes moves of `attack_type` miss this Pokemon.
+    pub fn is_immune_by_ability(&self, attack_type: &str) -> bool {
+        self.ability
+            .is_some_and(|ability| ability.is_immune(attack_type))
+    }
+
+    pub fn get_item(&self) -> Option<&'static dyn HeldItem> {
+        self.item
+    }
+
+    /// Removes the held item, e.g. after eating a berry.
+    pub fn consume_item(&mut self) {
+        self.item = None;
+    }
+
+    /// Multiplier the held item puts on the damage of `used_move`.
+    pub fn get_item_damage_modifier(&self, used_move: &Move) -> f64 {
+        self.item
+            .map_or(1.0, |item| item.damage_modifier(used_move))
+    }
+
+    pub fn get_locked_move(&self) -> Option<usize> {
+        self.locked_move
+    }
+
+    pub fn get_status(&self) -> Option<StatusCondition> {
+        self.status
+    }
+
+    /// Gives the Pokemon `status` unless it already has a condition, is
+    /// immune to it or has fainted. Returns whether the status was applied.
+    pub fn inflict_status(&mut self, status: StatusCondition, rng: &mut dyn RngCore) -> bool {
+        if self.status.is_some() || self.is_fainted() || status.is_immune(&self.get_types()) {
+            return false;
+        }
+        if status == StatusCondition::Sleep {
+            self.sleep_turns = rng.random_range(SLEEP_TURNS);
+        }
+        self.status = Some(status);
+        true
+    }
+
+    pub fn cure_status(&mut self) {
+        self.status = None;
+        self.sleep_turns = 0;
+    }
+
+    /// Whether the status condition lets the Pokemon act this turn.
+    ///
+    /// Sleep counts down and wakes the Pokemon once it has slept its turns,
+    /// freeze may thaw, paralysis sometimes keeps it from moving.
+    pub fn try_to_move(&mut self, rng: &mut dyn RngCore) -> bool {
+        match self.status {
+            Some(StatusCondition::Sleep) if self.sleep_turns > 0 => {
+                self.sleep_turns -= 1;
+                false
+            }
+            Some(StatusCondition::Sleep) => {
+                self.cure_status();
+                true
+            }
+            Some(StatusCondition::Freeze) => {
+                let thawed = rng.random_bool(THAW_CHANCE);
+                if thawed {
+                    self.cure_status();
+                }
+                thawed
+            }
+            Some(StatusCondition::Paralysis) => !rng.random_bool(PARALYSIS_SKIP_CHANCE),
+            _ => true,
+        }
+    }
+
+    /// End of turn damage from burn or poison. Returns the HP lost.
+    pub fn take_residual_damage(&mut self) -> i64 {
+        let damage = self
+            .status
+            .map_or(0, |status| status.residual_damage(self.max_hp))
+            .min(self.current_hp);
+        self.take_damage(damage);
+        damage
+    }
+
     pub fn get_name(&self) -> &str {
         self.pokemon.get_name()
     }
+
+    pub fn get_pokemon(&self) -> &'a Pokemon {
+        self.pokemon
+    }
+
+    pub fn get_current_hp(&self) -> i64 {
+        self.current_hp
+    }
+
+    pub fn get_level(&self) -> u8 {
+        self.level
+    }
+
+    pub fn get_max_hp(&self) -> i64 {
+        self.max_hp
+    }
     pub fn get_type(&self) -> &str {
         self.pokemon.get_primary_type()
     }
+
+    pub fn get_types(&self) -> Vec<&str> {
+        self.pokemon.get_types()
+    }
+    /// Speed after stat stages and the held item. Paralysis halves it.
     pub fn get_speed(&self) -> i64 {
-        self.pokemon.get_speed()
+        let speed = self.effective_stat("speed", self.stats.speed, self.stages.speed);
+        match self.status {
+            Some(StatusCondition::Paralysis) => speed / 2,
+            _ => speed,
+        }
     }
 
+    /// Attack after stat stages and the held item. A burn halves it,
+    /// weakening physical moves.
     pub fn get_attack(&self) -> i64 {
-        self.pokemon.get_attack()
+        let attack = self.effective_stat("attack", self.stats.attack, self.stages.attack);
+        match self.status {
+            Some(StatusCondition::Burn) => attack / 2,
+            _ => attack,
+        }
     }
 
     pub fn get_defense(&self) -> i64 {
-        self.pokemon.get_defense()
+        self.effective_stat("defense", self.stats.defense, self.stages.defense)
+    }
+
+    pub fn get_special_attack(&self) -> i64 {
+        self.effective_stat(
+            "special-attack",
+            self.stats.special_attack,
+            self.stages.special_attack,
+        )
+    }
+
+    pub fn get_special_defense(&self) -> i64 {
+        self.effective_stat(
+            "special-defense",
+            self.stats.special_defense,
+            self.stages.special_defense,
+        )
+    }
+
+    /// `value` of `stat` scaled by its stage and the held item.
+    fn effective_stat(&self, stat: &str, value: i64, stage: i8) -> i64 {
+        let item = self.item.map_or(1.0, |item| item.stat_modifier(stat));
+        (value as f64 * stat_multiplier(stage) * item) as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pokemon_choice() {
+        let parse = |s: &str| s.parse::<PokemonChoice>().ok();
+        assert_eq!(parse("25"), Some(PokemonChoice::Id(25)));
+        assert_eq!(
+            parse(" Pikachu "),
+            Some(PokemonChoice::Name("pikachu".into()))
+        );
+        assert_eq!(
+            parse("Mr. Mime"),
+            Some(PokemonChoice::Name("mr-mime".into()))
+        );
+        assert_eq!(parse("random"), Some(PokemonChoice::Random));
+        assert!(matches!(
+            "  ".parse::<PokemonChoice>(),
+            Err(PokeFightError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_deserialize_pokemon_choice() {
+        let choices: Vec<PokemonChoice> = serde_json::from_str(r#"[25, "25", "Eevee"]"#).unwrap();
+        assert_eq!(
+            choices,
+            vec![
+                PokemonChoice::Id(25),
+                PokemonChoice::Id(25),
+                PokemonChoice::Name("eevee".into()),
+            ]
+        );
+        assert!(serde_json::from_str::<Vec<PokemonChoice>>(r#"[""]"#).is_err());
+    }
+
+    #[test]
+    fn test_battle_ability_is_first_implemented_regular_one() {
+        let geodude: Pokemon = serde_json::from_value(serde_json::json!({
+            "name": "geodude",
+            "stats": [],
+            "types": [{ "type": { "name": "rock" } }],
+            "abilities": [
+                { "ability": { "name": "rock-head" }, "is_hidden": false, "slot": 1 },
+                { "ability": { "name": "sturdy" }, "is_hidden": false, "slot": 2 },
+                { "ability": { "name": "sand-veil" }, "is_hidden": true, "slot": 3 }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(geodude.get_ability_names(), ["rock-head", "sturdy"]);
+        let ability = BattlePokemon::new(&geodude).get_ability().unwrap();
+        assert_eq!(ability.name(), "sturdy");
     }
 }
@@ -0,0 +1,221 @@
+use std::path::Path;
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use super::battle::{items, strategy};
+use super::pokemon::PokemonChoice;
+use super::trainer::Trainer;
+use crate::errors::{PokeFightError, Result};
+use crate::pokeservice::batch::BatchOptions;
+use crate::pokeservice::pool::DraftPool;
+use crate::pokeservice::PokemonSource;
+
+/// Trainers entering a tournament, loaded from a roster file:
+///
+/// ```toml
+/// [[trainers]]
+/// name = "Ash"
+/// pokemon = "pikachu"
+/// item = "leftovers"
+/// strategy = "lookahead"
+///
+/// [[trainers]]
+/// name = "Misty"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Roster {
+    pub trainers: Vec<RosterEntry>,
+}
+
+/// One trainer of a roster. The Pokemon is drawn at random when missing and
+/// leads the team when the tournament has teams.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RosterEntry {
+    pub name: String,
+    #[serde(default)]
+    pub pokemon: PokemonChoice,
+    /// Held item of the lead, e.g. `leftovers`. The rest of the team holds
+    /// nothing.
+    #[serde(default)]
+    pub item: Option<String>,
+    /// Battle strategy, e.g. `greedy`. Type-aware when missing.
+    #[serde(default)]
+    pub strategy: Option<String>,
+}
+
+impl Roster {
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| PokeFightError::ConfigError(format!("Invalid roster: {}", e)))
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        toml::from_str(toml)
+            .map_err(|e| PokeFightError::ConfigError(format!("Invalid roster: {}", e)))
+    }
+
+    /// Loads a roster from a `.json` or `.toml` file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json(&contents),
+            Some("toml") => Self::from_toml(&contents),
+            _ => Err(PokeFightError::ConfigError(format!(
+                "Roster must be a .json or .toml file: {}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Drafts a team of `team_size` from `pool` for every trainer, in roster
+    /// order. A trainer's pick leads the team, the rest is drawn at random.
+    /// Items and strategies are checked before any Pokemon is fetched.
+    pub async fn draft_trainers(
+        &self,
+        pool: &DraftPool,
+        source: &dyn PokemonSource,
+        team_size: usize,
+        options: &BatchOptions,
+        rng: &mut (dyn RngCore + Send),
+    ) -> Result<Vec<Trainer>> {
+        let held_items = self
+            .trainers
+            .iter()
+            .map(|t| t.item.as_deref().map(items::parse).transpose())
+            .collect::<Result<Vec<_>>>()?;
+        let strategies = self
+            .trainers
+            .iter()
+            .map(|t| t.strategy.as_deref().map(strategy::parse).transpose())
+            .collect::<Result<Vec<_>>>()?;
+
+        let team_size = team_size.max(1);
+        let choices = self
+            .trainers
+            .iter()
+            .flat_map(|t| {
+                let mut choices = vec![t.pokemon.clone()];
+                choices.resize(team_size, PokemonChoice::Random);
+                choices
+            })
+            .collect();
+        let mut drawn = pool.draft(source, choices, options, rng).await.into_iter();
+
+        let mut trainers = Vec::with_capacity(self.trainers.len());
+        for ((entry, item), strategy) in self.trainers.iter().zip(held_items).zip(strategies) {
+            let team = drawn
+                .by_ref()
+                .take(team_size)
+                .collect::<Result<Vec<_>>>()
+                .map_err(|e| PokeFightError::DraftFailed {
+                    trainer: entry.name.clone(),
+                    source: Box::new(e),
+                })?;
+            let mut trainer = Trainer::with_team(entry.name.clone(), team)?;
+            if let Some(item) = item {
+                trainer = trainer.with_item(item);
+            }
+            if let Some(strategy) = strategy {
+                trainer = trainer.with_strategy(strategy);
+            }
+            trainers.push(trainer);
+        }
+        Ok(trainers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pokeservice::offline::OfflineSource;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_rosters() {
+        let toml = Roster::from_toml(
+            "[[trainers]]\nname = \"Ash\"\npokemon = \"Pikachu\"\nitem = \"Choice Band\"\n\
+             strategy = \"greedy\"\n\n\
+             [[trainers]]\nname = \"Misty\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            toml.trainers,
+            vec![
+                RosterEntry {
+                    name: "Ash".to_string(),
+                    pokemon: PokemonChoice::Name("pikachu".to_string()),
+                    item: Some("Choice Band".to_string()),
+                    strategy: Some("greedy".to_string()),
+                },
+                RosterEntry {
+                    name: "Misty".to_string(),
+                    pokemon: PokemonChoice::Random,
+                    item: None,
+                    strategy: None,
+                },
+            ]
+        );
+
+        assert!(items::parse(toml.trainers[0].item.as_deref().unwrap()).is_ok());
+
+        let json =
+            Roster::from_json(r#"{ "trainers": [{ "name": "Brock", "pokemon": 95 }] }"#).unwrap();
+        assert_eq!(json.trainers[0].pokemon, PokemonChoice::Id(95));
+        assert!(Roster::from_json(r#"{ "trainers": [{ "pokemon": 95 }] }"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_draft_trainers() {
+        let entry =
+            |name: &str, pokemon: &str, item: Option<&str>, strategy: Option<&str>| RosterEntry {
+                name: name.to_string(),
+                pokemon: pokemon.parse().unwrap(),
+                item: item.map(str::to_string),
+                strategy: strategy.map(str::to_string),
+            };
+        let draft = |roster: Roster| async move {
+            roster
+                .draft_trainers(
+                    &DraftPool::default(),
+                    &OfflineSource::new(),
+                    3,
+                    &BatchOptions::default(),
+                    &mut StdRng::seed_from_u64(7),
+                )
+                .await
+        };
+
+        let trainers = draft(Roster {
+            trainers: vec![
+                entry("Ash", "pikachu", Some("leftovers"), Some("greedy")),
+                entry("Misty", "staryu", None, None),
+            ],
+        })
+        .await
+        .unwrap();
+        assert_eq!(trainers.len(), 2);
+        assert!(trainers.iter().all(|t| t.get_team().len() == 3));
+        assert_eq!(trainers[0].get_team()[0].name, "pikachu");
+        assert_eq!(trainers[0].get_strategy().name(), "greedy");
+        assert_eq!(trainers[1].get_strategy().name(), "type-aware");
+
+        let error = draft(Roster {
+            trainers: vec![entry("Brock", "missingno", None, None)],
+        })
+        .await
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            PokeFightError::DraftFailed { ref trainer, .. } if trainer == "Brock"
+        ));
+
+        let error = draft(Roster {
+            trainers: vec![entry("Brock", "onix", Some("soft-pillow"), None)],
+        })
+        .await
+        .unwrap_err();
+        assert!(matches!(error, PokeFightError::UnknownItem(_)));
+    }
+}
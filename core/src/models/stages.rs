@@ -0,0 +1,107 @@
+/// Lowest and highest stage a stat can be moved to.
+pub const MIN_STAGE: i8 = -6;
+pub const MAX_STAGE: i8 = 6;
+
+/// In-battle stat modifiers, from -6 to +6. Every Pokemon starts at 0.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatStages {
+    pub attack: i8,
+    pub defense: i8,
+    pub special_attack: i8,
+    pub special_defense: i8,
+    pub speed: i8,
+    pub accuracy: i8,
+    pub evasion: i8,
+}
+
+impl StatStages {
+    /// Stage of a stat by its PokeAPI name, 0 for stats without stages.
+    pub fn get(&self, stat: &str) -> i8 {
+        match stat {
+            "attack" => self.attack,
+            "defense" => self.defense,
+            "special-attack" => self.special_attack,
+            "special-defense" => self.special_defense,
+            "speed" => self.speed,
+            "accuracy" => self.accuracy,
+            "evasion" => self.evasion,
+            _ => 0,
+        }
+    }
+
+    /// Moves a stat by `delta` stages, stopping at -6 and +6. Returns how
+    /// far it actually moved, 0 if it was already at the limit.
+    pub fn change(&mut self, stat: &str, delta: i8) -> i8 {
+        let stage = match stat {
+            "attack" => &mut self.attack,
+            "defense" => &mut self.defense,
+            "special-attack" => &mut self.special_attack,
+            "special-defense" => &mut self.special_defense,
+            "speed" => &mut self.speed,
+            "accuracy" => &mut self.accuracy,
+            "evasion" => &mut self.evasion,
+            _ => return 0,
+        };
+        let before = *stage;
+        *stage = before.saturating_add(delta).clamp(MIN_STAGE, MAX_STAGE);
+        *stage - before
+    }
+}
+
+/// Multiplier of attack, defense, special attack, special defense and speed
+/// stages: 2/2 at 0, up to 8/2 at +6 and down to 2/8 at -6.
+pub fn stat_multiplier(stage: i8) -> f64 {
+    let stage = stage.clamp(MIN_STAGE, MAX_STAGE) as f64;
+    if stage >= 0.0 {
+        (2.0 + stage) / 2.0
+    } else {
+        2.0 / (2.0 - stage)
+    }
+}
+
+/// Multiplier of accuracy and evasion stages: 3/3 at 0, up to 9/3 at +6
+/// and down to 3/9 at -6.
+pub fn accuracy_multiplier(stage: i8) -> f64 {
+    let stage = stage.clamp(MIN_STAGE, MAX_STAGE) as f64;
+    if stage >= 0.0 {
+        (3.0 + stage) / 3.0
+    } else {
+        3.0 / (3.0 - stage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accuracy_multiplier() {
+        assert_eq!(accuracy_multiplier(0), 1.0);
+        assert_eq!(accuracy_multiplier(1), 4.0 / 3.0);
+        assert_eq!(accuracy_multiplier(-1), 0.75);
+        assert_eq!(accuracy_multiplier(6), 3.0);
+        assert_eq!(accuracy_multiplier(-12), 1.0 / 3.0);
+    }
+
+    #[test]
+    fn test_stat_multiplier() {
+        assert_eq!(stat_multiplier(0), 1.0);
+        assert_eq!(stat_multiplier(2), 2.0);
+        assert_eq!(stat_multiplier(-1), 2.0 / 3.0);
+        assert_eq!(stat_multiplier(6), 4.0);
+        assert_eq!(stat_multiplier(-6), 0.25);
+    }
+
+    #[test]
+    fn test_changes_stop_at_limits() {
+        let mut stages = StatStages::default();
+
+        assert_eq!(stages.change("attack", 2), 2);
+        assert_eq!(stages.change("attack", 6), 4);
+        assert_eq!(stages.change("attack", 1), 0);
+        assert_eq!(stages.get("attack"), MAX_STAGE);
+        assert_eq!(stages.change("speed", -8), -6);
+        assert_eq!(stages.speed, MIN_STAGE);
+        assert_eq!(stages.change("hp", 1), 0);
+    }
+}
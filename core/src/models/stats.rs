@@ -0,0 +1,351 @@
+use std::fmt;
+
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use super::pokemon::Pokemon;
+
+/// Level every Pokemon fights at unless told otherwise.
+pub const DEFAULT_LEVEL: u8 = 50;
+pub const MAX_LEVEL: u8 = 100;
+
+/// Highest individual value a stat can have.
+pub const MAX_IV: u8 = 31;
+
+/// Highest effort values a single stat and all stats together can have.
+pub const MAX_EV: u8 = 252;
+pub const MAX_TOTAL_EVS: u16 = 510;
+
+/// One value per stat, used for IVs, EVs and calculated stats.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatValues<T> {
+    pub hp: T,
+    pub attack: T,
+    pub defense: T,
+    pub special_attack: T,
+    pub special_defense: T,
+    pub speed: T,
+}
+
+impl<T: Copy> StatValues<T> {
+    /// The same value for every stat.
+    pub fn uniform(value: T) -> Self {
+        Self {
+            hp: value,
+            attack: value,
+            defense: value,
+            special_attack: value,
+            special_defense: value,
+            speed: value,
+        }
+    }
+}
+
+/// Raises one stat by 10% and lowers another by 10%. Hardy, Docile,
+/// Serious, Bashful and Quirky change nothing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Nature {
+    #[default]
+    Hardy,
+    Lonely,
+    Brave,
+    Adamant,
+    Naughty,
+    Bold,
+    Docile,
+    Relaxed,
+    Impish,
+    Lax,
+    Timid,
+    Hasty,
+    Serious,
+    Jolly,
+    Naive,
+    Modest,
+    Mild,
+    Quiet,
+    Bashful,
+    Rash,
+    Calm,
+    Gentle,
+    Sassy,
+    Careful,
+    Quirky,
+}
+
+impl Nature {
+    pub const ALL: [Nature; 25] = [
+        Nature::Hardy,
+        Nature::Lonely,
+        Nature::Brave,
+        Nature::Adamant,
+        Nature::Naughty,
+        Nature::Bold,
+        Nature::Docile,
+        Nature::Relaxed,
+        Nature::Impish,
+        Nature::Lax,
+        Nature::Timid,
+        Nature::Hasty,
+        Nature::Serious,
+        Nature::Jolly,
+        Nature::Naive,
+        Nature::Modest,
+        Nature::Mild,
+        Nature::Quiet,
+        Nature::Bashful,
+        Nature::Rash,
+        Nature::Calm,
+        Nature::Gentle,
+        Nature::Sassy,
+        Nature::Careful,
+        Nature::Quirky,
+    ];
+
+    /// The raised and the lowered stat, `None` for neutral natures.
+    pub fn modified_stats(&self) -> Option<(&'static str, &'static str)> {
+        // Natures are laid out in a 5x5 grid: the row is the raised stat,
+        // the column the lowered one
+        const STATS: [&str; 5] = [
+            "attack",
+            "defense",
+            "speed",
+            "special-attack",
+            "special-defense",
+        ];
+        let index = *self as usize;
+        let (raised, lowered) = (index / 5, index % 5);
+        (raised != lowered).then(|| (STATS[raised], STATS[lowered]))
+    }
+
+    /// Applies the nature to the calculated value of `stat`.
+    pub fn apply(&self, stat: &str, value: i64) -> i64 {
+        match self.modified_stats() {
+            Some((raised, _)) if raised == stat => value * 11 / 10,
+            Some((_, lowered)) if lowered == stat => value * 9 / 10,
+            _ => value,
+        }
+    }
+}
+
+impl fmt::Display for Nature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = format!("{:?}", self).to_lowercase();
+        write!(f, "{}", name)
+    }
+}
+
+/// Everything besides the species that shapes a Pokemon's stats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PokemonBuild {
+    pub level: u8,
+    pub ivs: StatValues<u8>,
+    pub evs: StatValues<u8>,
+    pub nature: Nature,
+}
+
+impl Default for PokemonBuild {
+    /// Level 50, perfect IVs, no EVs and a neutral nature.
+    fn default() -> Self {
+        Self {
+            level: DEFAULT_LEVEL,
+            ivs: StatValues::uniform(MAX_IV),
+            evs: StatValues::default(),
+            nature: Nature::default(),
+        }
+    }
+}
+
+impl PokemonBuild {
+    pub fn with_level(mut self, level: u8) -> Self {
+        self.level = level.clamp(1, MAX_LEVEL);
+        self
+    }
+
+    /// Rolls new IVs and a new nature, keeping level and EVs.
+    pub fn randomized(mut self, rng: &mut dyn RngCore) -> Self {
+        let mut roll = || rng.random_range(0..=MAX_IV);
+        self.ivs = StatValues {
+            hp: roll(),
+            attack: roll(),
+            defense: roll(),
+            special_attack: roll(),
+            special_defense: roll(),
+            speed: roll(),
+        };
+        self.nature = Nature::ALL[rng.random_range(0..Nature::ALL.len())];
+        self
+    }
+
+    /// EVs as they count towards the stats: at most [`MAX_EV`] per stat and
+    /// [`MAX_TOTAL_EVS`] in total. Once the total runs out, the stats in the
+    /// order hp, attack, defense, special attack, special defense, speed
+    /// keep theirs and the later ones lose the rest.
+    pub fn effective_evs(&self) -> StatValues<u8> {
+        let mut left = MAX_TOTAL_EVS;
+        let mut spend = |ev: u8| {
+            let spent = (ev.min(MAX_EV) as u16).min(left);
+            left -= spent;
+            spent as u8
+        };
+        StatValues {
+            hp: spend(self.evs.hp),
+            attack: spend(self.evs.attack),
+            defense: spend(self.evs.defense),
+            special_attack: spend(self.evs.special_attack),
+            special_defense: spend(self.evs.special_defense),
+            speed: spend(self.evs.speed),
+        }
+    }
+
+    /// Stats of `pokemon` with this build, following the main-series formulas:
+    ///
+    /// `hp = (2 * base + iv + ev / 4) * level / 100 + level + 10`
+    ///
+    /// `stat = ((2 * base + iv + ev / 4) * level / 100 + 5) * nature`
+    pub fn stats(&self, pokemon: &Pokemon) -> StatValues<i64> {
+        let level = self.level.clamp(1, MAX_LEVEL) as i64;
+        let evs = self.effective_evs();
+        let scaled = |base: i64, iv: u8, ev: u8| {
+            let iv = iv.min(MAX_IV) as i64;
+            (2 * base + iv + ev as i64 / 4) * level / 100
+        };
+        let stat = |name: &str, base: i64, iv: u8, ev: u8| {
+            self.nature.apply(name, scaled(base, iv, ev) + 5)
+        };
+
+        StatValues {
+            hp: scaled(pokemon.get_hp(), self.ivs.hp, evs.hp) + level + 10,
+            attack: stat("attack", pokemon.get_attack(), self.ivs.attack, evs.attack),
+            defense: stat(
+                "defense",
+                pokemon.get_defense(),
+                self.ivs.defense,
+                evs.defense,
+            ),
+            special_attack: stat(
+                "special-attack",
+                pokemon.get_special_attack(),
+                self.ivs.special_attack,
+                evs.special_attack,
+            ),
+            special_defense: stat(
+                "special-defense",
+                pokemon.get_special_defense(),
+                self.ivs.special_defense,
+                evs.special_defense,
+            ),
+            speed: stat("speed", pokemon.get_speed(), self.ivs.speed, evs.speed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_utils::create_test_pokemon;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_natures() {
+        assert_eq!(Nature::Hardy.modified_stats(), None);
+        assert_eq!(Nature::Quirky.modified_stats(), None);
+        assert_eq!(
+            Nature::Adamant.modified_stats(),
+            Some(("attack", "special-attack"))
+        );
+        assert_eq!(Nature::Timid.modified_stats(), Some(("speed", "attack")));
+        assert_eq!(
+            Nature::Careful.modified_stats(),
+            Some(("special-defense", "special-attack"))
+        );
+        assert_eq!(Nature::Modest.to_string(), "modest");
+    }
+
+    #[test]
+    fn test_stats_follow_main_series_formula() {
+        // Bulbapedia's example Garchomp
+        let garchomp: Pokemon = serde_json::from_value(serde_json::json!({
+            "name": "garchomp",
+            "stats": [
+                { "base_stat": 108, "stat": { "name": "hp" } },
+                { "base_stat": 130, "stat": { "name": "attack" } },
+                { "base_stat": 95, "stat": { "name": "defense" } },
+                { "base_stat": 80, "stat": { "name": "special-attack" } },
+                { "base_stat": 85, "stat": { "name": "special-defense" } },
+                { "base_stat": 102, "stat": { "name": "speed" } }
+            ],
+            "types": [{ "type": { "name": "dragon" } }]
+        }))
+        .unwrap();
+        let build = PokemonBuild {
+            level: 78,
+            ivs: StatValues {
+                hp: 24,
+                attack: 12,
+                defense: 30,
+                special_attack: 16,
+                special_defense: 23,
+                speed: 5,
+            },
+            evs: StatValues {
+                hp: 74,
+                attack: 190,
+                defense: 91,
+                special_attack: 48,
+                special_defense: 84,
+                speed: 23,
+            },
+            nature: Nature::Adamant,
+        };
+
+        assert_eq!(
+            build.stats(&garchomp),
+            StatValues {
+                hp: 289,
+                attack: 278,
+                defense: 193,
+                special_attack: 135,
+                special_defense: 171,
+                speed: 171,
+            }
+        );
+    }
+
+    #[test]
+    fn test_evs_are_capped_at_the_total() {
+        let maxed = PokemonBuild {
+            evs: StatValues::uniform(255),
+            ..PokemonBuild::default()
+        };
+        let capped = PokemonBuild {
+            evs: StatValues {
+                hp: 252,
+                attack: 252,
+                defense: 6,
+                ..StatValues::default()
+            },
+            ..PokemonBuild::default()
+        };
+        let mewtwo = create_test_pokemon("mewtwo", 106, 110, 90, 154, "psychic");
+
+        assert_eq!(maxed.effective_evs(), capped.evs);
+        assert_eq!(maxed.stats(&mewtwo), capped.stats(&mewtwo));
+        assert_ne!(maxed.stats(&mewtwo), PokemonBuild::default().stats(&mewtwo));
+    }
+
+    #[test]
+    fn test_randomized_builds_differ() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let build = PokemonBuild::default().with_level(30);
+
+        let first = build.randomized(&mut rng);
+        let second = build.randomized(&mut rng);
+
+        assert_ne!(first, second);
+        assert_eq!(first.level, 30);
+        assert_eq!(first.evs, build.evs);
+    }
+}
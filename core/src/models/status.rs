@@ -0,0 +1,99 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Chance that a paralyzed Pokemon cannot move on its turn.
+pub const PARALYSIS_SKIP_CHANCE: f64 = 0.25;
+
+/// Chance that a frozen Pokemon thaws out at the start of its turn.
+pub const THAW_CHANCE: f64 = 0.2;
+
+/// A sleeping Pokemon wakes up after this many of its turns.
+pub const SLEEP_TURNS: std::ops::RangeInclusive<u8> = 1..=3;
+
+/// A non-volatile status condition. A Pokemon can only have one at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusCondition {
+    /// Loses 1/16 of its max HP every turn and deals half physical damage.
+    Burn,
+    /// Loses 1/8 of its max HP every turn.
+    Poison,
+    /// Half speed and a chance to be unable to move.
+    Paralysis,
+    /// Cannot move until it wakes up.
+    Sleep,
+    /// Cannot move until it thaws out.
+    Freeze,
+}
+
+impl StatusCondition {
+    /// Maps a PokeAPI ailment name, ignoring volatile ones like `confusion`.
+    pub fn from_ailment(name: &str) -> Option<Self> {
+        match name {
+            "burn" => Some(StatusCondition::Burn),
+            "poison" => Some(StatusCondition::Poison),
+            "paralysis" => Some(StatusCondition::Paralysis),
+            "sleep" => Some(StatusCondition::Sleep),
+            "freeze" => Some(StatusCondition::Freeze),
+            _ => None,
+        }
+    }
+
+    /// Some types cannot get certain conditions, e.g. fire types are never burned.
+    pub fn is_immune(&self, types: &[&str]) -> bool {
+        let immune_types: &[&str] = match self {
+            StatusCondition::Burn => &["fire"],
+            StatusCondition::Poison => &["poison", "steel"],
+            StatusCondition::Paralysis => &["electric"],
+            StatusCondition::Freeze => &["ice"],
+            StatusCondition::Sleep => &[],
+        };
+        types.iter().any(|t| immune_types.contains(t))
+    }
+
+    /// HP lost at the end of every turn.
+    pub fn residual_damage(&self, max_hp: i64) -> i64 {
+        match self {
+            StatusCondition::Burn => (max_hp / 16).max(1),
+            StatusCondition::Poison => (max_hp / 8).max(1),
+            _ => 0,
+        }
+    }
+}
+
+impl fmt::Display for StatusCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            StatusCondition::Burn => "burn",
+            StatusCondition::Poison => "poison",
+            StatusCondition::Paralysis => "paralysis",
+            StatusCondition::Sleep => "sleep",
+            StatusCondition::Freeze => "freeze",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_immunities() {
+        assert!(StatusCondition::Burn.is_immune(&["fire", "flying"]));
+        assert!(StatusCondition::Poison.is_immune(&["grass", "poison"]));
+        assert!(StatusCondition::Paralysis.is_immune(&["electric"]));
+        assert!(StatusCondition::Freeze.is_immune(&["ice"]));
+        assert!(!StatusCondition::Sleep.is_immune(&["psychic"]));
+        assert!(!StatusCondition::Burn.is_immune(&["water"]));
+    }
+
+    #[test]
+    fn test_residual_damage() {
+        assert_eq!(StatusCondition::Burn.residual_damage(160), 10);
+        assert_eq!(StatusCondition::Poison.residual_damage(160), 20);
+        assert_eq!(StatusCondition::Poison.residual_damage(4), 1);
+        assert_eq!(StatusCondition::Paralysis.residual_damage(160), 0);
+    }
+}
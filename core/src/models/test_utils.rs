@@ -1,3 +1,4 @@
+use crate::models::moves::{DamageClass, DamageClassInfo, Move};
 use crate::models::pokemon::{Pokemon, Stat, StatName, Type, TypeInfo};
 
 pub fn create_test_pokemon(
@@ -9,6 +10,7 @@ pub fn create_test_pokemon(
     type_name: &str,
 ) -> Pokemon {
     Pokemon {
+        id: 0,
         name: name.to_string(),
         stats: vec![
             Stat {
@@ -41,5 +43,24 @@ pub fn create_test_pokemon(
                 name: type_name.to_string(),
             },
         }],
+        moves: vec![],
+        abilities: vec![],
+    }
+}
+
+pub fn create_test_move(name: &str, power: i64, type_name: &str, class: DamageClass) -> Move {
+    Move {
+        name: name.to_string(),
+        power: Some(power),
+        accuracy: Some(100),
+        pp: 10,
+        priority: 0,
+        type_info: TypeInfo {
+            name: type_name.to_string(),
+        },
+        damage_class: DamageClassInfo { name: class },
+        target: None,
+        stat_changes: vec![],
+        meta: None,
     }
 }
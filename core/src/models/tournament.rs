@@ -1,8 +1,15 @@
 use crate::errors::{PokeFightError, Result};
-use crate::models::trainer::Trainer;
+use crate::models::trainer::{Trainer, MAX_TEAM_SIZE};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 
-use super::battle::Battle;
+use super::battle::field::Arena;
+use super::battle::log::{BattleEvent, BattleLog};
+use super::battle::{Battle, BattleFormat, BattleRules, Finished, RoundReport};
+use super::damage::DamageModel;
+use super::type_chart::TypeChart;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MatchResult {
@@ -13,6 +20,18 @@ pub struct MatchResult {
     pub winner: String,
     pub looser: String,
     pub round: usize,
+    #[serde(default)]
+    pub critical_hits: usize,
+    #[serde(default)]
+    pub misses: usize,
+    /// Team members that fought, in the order they entered the battle.
+    #[serde(default)]
+    pub fighter1_team: Vec<String>,
+    #[serde(default)]
+    pub fighter2_team: Vec<String>,
+    /// Everything that happened in the battle, empty for a free pass.
+    #[serde(default)]
+    pub log: BattleLog,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,12 +40,25 @@ pub struct TournamentResult {
     pub chore: String,
     pub matches: Vec<MatchResult>,
     pub champion: String,
+    /// Re-running the same roster with this seed reproduces the bracket.
+    #[serde(default)]
+    pub seed: u64,
+    /// Weather and terrain every battle was fought in.
+    #[serde(default)]
+    pub arena: Arena,
+    #[serde(default)]
+    pub format: BattleFormat,
 }
 
 pub struct Tournament {
     name: String,
     chore: String,
     participants: Vec<Trainer>,
+    rules: BattleRules,
+    seed: u64,
+    level: Option<u8>,
+    random_builds: bool,
+    team_size: Option<usize>,
 }
 
 impl Tournament {
@@ -35,9 +67,62 @@ impl Tournament {
             name: name.into(),
             chore: chore.into(),
             participants,
+            rules: BattleRules::default(),
+            seed: rand::random(),
+            level: None,
+            random_builds: false,
+            team_size: None,
         }
     }
 
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Every Pokemon fights at `level` instead of its trainer's build level.
+    pub fn with_level(mut self, level: u8) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Gives every trainer's Pokemon random IVs and a random nature, so
+    /// that trainers with the same species still get distinct fighters.
+    pub fn with_random_builds(mut self, random_builds: bool) -> Self {
+        self.random_builds = random_builds;
+        self
+    }
+
+    pub fn with_type_chart(mut self, type_chart: TypeChart) -> Self {
+        self.rules.type_chart = type_chart;
+        self
+    }
+
+    pub fn with_damage_model(mut self, damage_model: Box<dyn DamageModel>) -> Self {
+        self.rules.damage_model = damage_model;
+        self
+    }
+
+    /// Every trainer fields the first `size` Pokemon of its team: 1 for
+    /// singles, up to 6. Without a team size, trainers bring whole teams.
+    pub fn with_team_size(mut self, size: usize) -> Self {
+        self.team_size = Some(size.clamp(1, MAX_TEAM_SIZE));
+        self
+    }
+
+    /// Fights every battle in the weather and terrain of `arena`.
+    pub fn with_arena(mut self, arena: Arena) -> Self {
+        self.rules.arena = arena;
+        self
+    }
+
+    /// Fights singles or doubles. Trainers with a single Pokemon field it
+    /// alone in doubles too.
+    pub fn with_format(mut self, format: BattleFormat) -> Self {
+        self.rules.format = format;
+        self
+    }
+
     pub fn get_name(&self) -> &str {
         &self.name
     }
@@ -46,51 +131,24 @@ impl Tournament {
         &self.chore
     }
 
-    fn get_type_effectiveness(&self, attacker_type: &str, defender_type: &str) -> f64 {
-        match (attacker_type, defender_type) {
-            // Fire advantages
-            ("fire", "grass") | ("fire", "ice") | ("fire", "bug") | ("fire", "steel") => 2.0,
-            ("fire", "water") | ("fire", "rock") | ("fire", "dragon") => 0.5,
-
-            // Water advantages
-            ("water", "fire") | ("water", "ground") | ("water", "rock") => 2.0,
-            ("water", "grass") | ("water", "dragon") => 0.5,
-
-            // Grass advantages
-            ("grass", "water") | ("grass", "ground") | ("grass", "rock") => 2.0,
-            ("grass", "fire")
-            | ("grass", "grass")
-            | ("grass", "poison")
-            | ("grass", "flying")
-            | ("grass", "bug")
-            | ("grass", "dragon")
-            | ("grass", "steel") => 0.5,
-
-            // Electric advantages
-            ("electric", "water") | ("electric", "flying") => 2.0,
-            ("electric", "grass") | ("electric", "electric") | ("electric", "dragon") => 0.5,
-            ("electric", "ground") => 0.0,
-
-            _ => 1.0, // Neutral
-        }
+    pub fn get_seed(&self) -> u64 {
+        self.seed
     }
 
     fn fight<'a>(
         &self,
         fighter_one: &'a Trainer,
         fighter_two: &'a Trainer,
-    ) -> (&'a Trainer, &'a Trainer) {
-        let mut battle = Battle::new(fighter_one, fighter_two).begin();
+        battle_seed: u64,
+    ) -> (Battle<'a, Finished>, Vec<RoundReport>) {
+        let mut battle = Battle::new(fighter_one, fighter_two, &self.rules, battle_seed).begin();
         let mut rounds = battle.rounds();
 
-        while rounds
-            .execute_round(|a, d| self.get_type_effectiveness(a, d))
-            .is_some()
-        {
-            // Round completed, continue
+        let mut reports = Vec::new();
+        while let Some(report) = rounds.execute_round(&self.rules) {
+            reports.push(report);
         }
-        let finished_battle = battle.finish();
-        (&finished_battle.winner(), &finished_battle.looser())
+        (battle.finish(), reports)
     }
 
     pub fn start(&self) -> Result<TournamentResult> {
@@ -99,17 +157,30 @@ impl Tournament {
                 self.participants.len(),
             ));
         }
+        if let Some(size) = self.team_size
+            && let Some(short) = self.participants.iter().find(|t| t.get_team().len() < size)
+        {
+            return Err(PokeFightError::TournamentError(format!(
+                "{} brings {} Pokemon, but teams of {} fight in this tournament",
+                short.get_name(),
+                short.get_team().len(),
+                size
+            )));
+        }
 
-        let mut current_fighters: Vec<&Trainer> = self.participants.iter().collect();
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let participants = self.prepare_participants(&mut rng);
+        let mut current_fighters: Vec<&Trainer> = participants.iter().collect();
+        current_fighters.shuffle(&mut rng);
         let mut round_num = 1;
         let mut all_matches: Vec<MatchResult> = Vec::new();
 
         while current_fighters.len() > 1 {
-            let round_matches = self.run_elimination_round(current_fighters, round_num);
+            let round_matches = self.run_elimination_round(current_fighters, round_num, &mut rng);
             current_fighters = round_matches
                 .iter()
                 .map(|m| {
-                    self.participants
+                    participants
                         .iter()
                         .find(|t| t.get_name() == m.winner)
                         .unwrap()
@@ -126,10 +197,40 @@ impl Tournament {
             chore: self.chore.clone(),
             matches: all_matches,
             champion,
+            seed: self.seed,
+            arena: self.rules.arena,
+            format: self.rules.format,
         })
     }
 
-    fn run_elimination_round(&self, fighters: Vec<&Trainer>, round_num: usize) -> Vec<MatchResult> {
+    /// Applies the tournament's level, random builds and team size to every
+    /// trainer.
+    fn prepare_participants(&self, rng: &mut StdRng) -> Vec<Trainer> {
+        self.participants
+            .iter()
+            .map(|trainer| {
+                let mut build = *trainer.get_build();
+                if let Some(level) = self.level {
+                    build = build.with_level(level);
+                }
+                if self.random_builds {
+                    build = build.randomized(rng);
+                }
+                let trainer = trainer.clone().with_build(build);
+                match self.team_size {
+                    Some(size) => trainer.truncate_team(size),
+                    None => trainer,
+                }
+            })
+            .collect()
+    }
+
+    fn run_elimination_round(
+        &self,
+        fighters: Vec<&Trainer>,
+        round_num: usize,
+        rng: &mut StdRng,
+    ) -> Vec<MatchResult> {
         let (pairs, free_pass_figter) = self.create_pairings(fighters);
         let mut matches = Vec::new();
 
@@ -142,19 +243,36 @@ impl Tournament {
                 looser: "free_pass".to_string(),
                 winner: fp_fighter.get_name().to_string(),
                 round: round_num,
+                critical_hits: 0,
+                misses: 0,
+                fighter1_team: Vec::new(),
+                fighter2_team: Vec::new(),
+                log: BattleLog::default(),
             });
         }
 
         for (f1, f2) in pairs.into_iter() {
-            let (winner, looser) = self.fight(f1, f2);
+            let (battle, reports) = self.fight(f1, f2, rng.random());
+            let (team1, team2) = battle.fielded();
+            let mut log = BattleLog::new(&reports);
+            if battle.timed_out() {
+                log.events.push(BattleEvent::TurnLimit {
+                    turns: reports.len(),
+                });
+            }
             matches.push(MatchResult {
                 fighter1: f1.get_name().to_string(),
                 fighter1_pokemon: f1.get_pokemon().get_name().to_string(),
                 fighter2: f2.get_name().to_string(),
                 fighter2_pokemon: f2.get_pokemon().get_name().to_string(),
-                winner: winner.get_name().to_string(),
-                looser: looser.get_name().to_string(),
+                winner: battle.winner().get_name().to_string(),
+                looser: battle.looser().get_name().to_string(),
                 round: round_num,
+                critical_hits: reports.iter().map(RoundReport::critical_hits).sum(),
+                misses: reports.iter().map(RoundReport::misses).sum(),
+                fighter1_team: team1,
+                fighter2_team: team2,
+                log,
             });
         }
 
@@ -183,7 +301,20 @@ impl Tournament {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::battle::log::Fighter;
+    use crate::models::battle::{items, Position, MAX_ROUNDS};
+    use crate::models::damage::MainSeriesDamage;
     use crate::models::test_utils::create_test_pokemon;
+    use crate::pokeservice::offline::OfflineSource;
+    use crate::pokeservice::PokemonSource;
+
+    fn without_luck() -> Box<dyn DamageModel> {
+        Box::new(MainSeriesDamage {
+            critical_chance: 0.0,
+            random_spread: false,
+            ..MainSeriesDamage::default()
+        })
+    }
 
     #[test]
     fn test_tournament_with_two_trainers() {
@@ -196,7 +327,9 @@ mod tests {
         let trainer2 = Trainer::new("Gary".to_string(), charmander);
 
         // Create tournament
-        let tournament = Tournament::new(vec![trainer1, trainer2], "Test Tournament", "Test Chore");
+        let tournament = Tournament::new(vec![trainer1, trainer2], "Test Tournament", "Test Chore")
+            .with_damage_model(without_luck())
+            .with_seed(1);
 
         // Start tournament
         let tournament_result = tournament.start();
@@ -214,6 +347,174 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_match_results_carry_battle_log() {
+        let pikachu = create_test_pokemon("pikachu", 39, 55, 40, 90, "electric");
+        let charmander = create_test_pokemon("charmander", 39, 52, 43, 65, "fire");
+        let trainers = vec![
+            Trainer::new("Ash".to_string(), pikachu),
+            Trainer::new("Gary".to_string(), charmander),
+        ];
+        let result = Tournament::new(trainers, "Test Tournament", "Test Chore")
+            .with_damage_model(without_luck())
+            .with_seed(1)
+            .start()
+            .unwrap();
+        let events = &result.matches[0].log.events;
+
+        // The faster lead comes in first, then the first round starts
+        let pikachu = Fighter::new(Position { side: 0, slot: 0 }, "pikachu");
+        let charmander = Fighter::new(Position { side: 1, slot: 0 }, "charmander");
+        assert!(matches!(
+            &events[..3],
+            [
+                BattleEvent::SwitchIn { fighter: first, .. },
+                BattleEvent::SwitchIn { fighter: second, .. },
+                BattleEvent::TurnStart { turn: 1 },
+            ] if *first == pikachu && *second == charmander
+        ));
+        assert!(matches!(&events[3], BattleEvent::MoveUsed { fighter, .. } if *fighter == pikachu));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            BattleEvent::Damage { target, effectiveness, .. }
+                if *target == charmander && *effectiveness == 1.0
+        )));
+        assert_eq!(
+            events.last(),
+            Some(&BattleEvent::Faint {
+                fighter: charmander
+            })
+        );
+
+        // The log survives a round trip through the JSON output
+        let json = serde_json::to_string(&result).unwrap();
+        let parsed: TournamentResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.matches[0].log, result.matches[0].log);
+    }
+
+    #[tokio::test]
+    async fn test_walls_holding_leftovers_finish_their_battle() {
+        let chansey = OfflineSource::new().by_name("chansey").await.unwrap();
+        let leftovers = items::parse("leftovers").unwrap();
+        let trainers = vec![
+            Trainer::new("Ash".to_string(), chansey.clone()).with_item(leftovers),
+            Trainer::new("Gary".to_string(), chansey).with_item(leftovers),
+        ];
+
+        let result = Tournament::new(trainers, "Test Tournament", "Test Chore")
+            .with_seed(1)
+            .start()
+            .unwrap();
+        let turns = result.matches[0]
+            .log
+            .events
+            .iter()
+            .filter(|event| matches!(event, BattleEvent::TurnStart { .. }))
+            .count();
+        assert!(turns <= MAX_ROUNDS, "battle took {turns} turns");
+        assert!(["Ash", "Gary"].contains(&result.champion.as_str()));
+    }
+
+    #[test]
+    fn test_same_seed_reproduces_bracket() {
+        let roster = || {
+            vec![
+                Trainer::new(
+                    "Ash".into(),
+                    create_test_pokemon("pikachu", 35, 55, 40, 90, "electric"),
+                ),
+                Trainer::new(
+                    "Gary".into(),
+                    create_test_pokemon("eevee", 55, 55, 50, 55, "normal"),
+                ),
+                Trainer::new(
+                    "Misty".into(),
+                    create_test_pokemon("staryu", 30, 45, 55, 85, "water"),
+                ),
+                Trainer::new(
+                    "Brock".into(),
+                    create_test_pokemon("onix", 35, 45, 160, 70, "rock"),
+                ),
+                Trainer::new(
+                    "Jessie".into(),
+                    create_test_pokemon("ekans", 35, 60, 44, 55, "poison"),
+                ),
+            ]
+        };
+        let run = |seed| {
+            let result = Tournament::new(roster(), "Seeded", "Test Chore")
+                .with_seed(seed)
+                .start()
+                .unwrap();
+            assert_eq!(result.seed, seed);
+            serde_json::to_string(&result.matches).unwrap()
+        };
+
+        assert_eq!(run(42), run(42));
+        assert_eq!(run(7), run(7));
+    }
+
+    #[test]
+    fn test_level_and_random_builds_apply_to_every_trainer() {
+        let eevee = create_test_pokemon("eevee", 55, 55, 50, 55, "normal");
+        let roster = vec![
+            Trainer::new("Ash".into(), eevee.clone()),
+            Trainer::new("Gary".into(), eevee),
+        ];
+        let tournament = Tournament::new(roster, "Builds", "Test Chore")
+            .with_level(100)
+            .with_random_builds(true);
+
+        let participants = tournament.prepare_participants(&mut StdRng::seed_from_u64(1));
+
+        let ash = participants[0].get_build();
+        let gary = participants[1].get_build();
+        assert_eq!((ash.level, gary.level), (100, 100));
+        assert_ne!(ash, gary, "same species should get distinct fighters");
+    }
+
+    #[test]
+    fn test_team_size_limits_every_team() {
+        let team = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| create_test_pokemon(name, 50, 50, 50, 50, "normal"))
+                .collect::<Vec<_>>()
+        };
+        assert!(matches!(
+            Trainer::with_team("Ash".into(), vec![]),
+            Err(PokeFightError::InvalidTeamSize(0))
+        ));
+        let roster = || {
+            vec![
+                Trainer::with_team("Ash".into(), team(&["pikachu", "bulbasaur", "lapras"]))
+                    .unwrap(),
+                Trainer::with_team("Gary".into(), team(&["eevee", "arcanine"])).unwrap(),
+            ]
+        };
+
+        let result = Tournament::new(roster(), "3v3", "Test Chore")
+            .with_team_size(3)
+            .start();
+        assert!(result.is_err(), "Gary cannot field three Pokemon");
+
+        let result = Tournament::new(roster(), "2v2", "Test Chore")
+            .with_team_size(2)
+            .with_seed(1)
+            .start()
+            .unwrap();
+        let final_match = &result.matches[0];
+        assert_eq!(final_match.fighter1_team[0], "pikachu");
+        assert!(!final_match.fighter1_team.contains(&"lapras".to_string()));
+        // The loser's whole team fought
+        let loser_team = if final_match.looser == "Ash" {
+            &final_match.fighter1_team
+        } else {
+            &final_match.fighter2_team
+        };
+        assert_eq!(loser_team.len(), 2);
+    }
+
     #[test]
     fn test_tournament_with_zero_trainers() {
         let tournament = Tournament::new(vec![], "Empty Tournament", "Test Chore");
@@ -255,7 +556,9 @@ mod tests {
             vec![trainer1, trainer2, trainer3],
             "Three Trainer Tournament",
             "Test Chore",
-        );
+        )
+        .with_damage_model(without_luck())
+        .with_seed(1);
 
         // Start tournament
         let tournament_result = tournament.start();
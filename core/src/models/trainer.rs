@@ -1,18 +1,107 @@
+use std::sync::Arc;
+
+use super::battle::items::HeldItem;
+use super::battle::strategy::{BattleStrategy, TypeAware};
 use super::pokemon::{BattlePokemon, Pokemon};
+use super::stats::PokemonBuild;
+use crate::errors::{PokeFightError, Result};
+
+/// Most Pokemon a trainer can carry.
+pub const MAX_TEAM_SIZE: usize = 6;
 
 #[derive(Debug, Clone)]
 pub struct Trainer {
     name: String,
-    pokemon: Pokemon,
+    /// The lead comes first.
+    team: Vec<Pokemon>,
+    build: PokemonBuild,
+    /// Only the lead holds it; the rest of the team goes without.
+    item: Option<&'static dyn HeldItem>,
+    /// Decides the trainer's moves and switches in battle.
+    strategy: Arc<dyn BattleStrategy>,
 }
 
 impl Trainer {
     pub fn new(name: String, pokemon: Pokemon) -> Self {
-        Self { name, pokemon }
+        Self {
+            name,
+            team: vec![pokemon],
+            build: PokemonBuild::default(),
+            item: None,
+            strategy: Arc::new(TypeAware),
+        }
+    }
+
+    /// A trainer with a team of one to six Pokemon, led by the first one.
+    pub fn with_team(name: String, team: Vec<Pokemon>) -> Result<Self> {
+        if team.is_empty() || team.len() > MAX_TEAM_SIZE {
+            return Err(PokeFightError::InvalidTeamSize(team.len()));
+        }
+        Ok(Self {
+            name,
+            team,
+            build: PokemonBuild::default(),
+            item: None,
+            strategy: Arc::new(TypeAware),
+        })
+    }
+
+    /// Every team member gets the same build.
+    pub fn with_build(mut self, build: PokemonBuild) -> Self {
+        self.build = build;
+        self
+    }
+
+    /// The lead holds the item, the other team members carry none.
+    pub fn with_item(mut self, item: &'static dyn HeldItem) -> Self {
+        self.item = Some(item);
+        self
+    }
+
+    /// Plays battles with `strategy` instead of the type-aware default.
+    pub fn with_strategy(mut self, strategy: Arc<dyn BattleStrategy>) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Keeps the first `size` team members, at least the lead.
+    pub fn truncate_team(mut self, size: usize) -> Self {
+        self.team.truncate(size.max(1));
+        self
     }
 
+    /// The lead, ready for battle.
     pub fn get_pokemon(&self) -> BattlePokemon<'_> {
-        BattlePokemon::new(&self.pokemon)
+        BattlePokemon::new(&self.team[0])
+            .with_build(self.build)
+            .with_item(self.item)
+    }
+
+    /// The whole team ready for battle, lead first.
+    pub fn get_battle_team(&self) -> Vec<BattlePokemon<'_>> {
+        let mut team = vec![self.get_pokemon()];
+        team.extend(
+            self.team[1..]
+                .iter()
+                .map(|pokemon| BattlePokemon::new(pokemon).with_build(self.build)),
+        );
+        team
+    }
+
+    pub fn get_team(&self) -> &[Pokemon] {
+        &self.team
+    }
+
+    pub fn get_item(&self) -> Option<&'static dyn HeldItem> {
+        self.item
+    }
+
+    pub fn get_strategy(&self) -> &dyn BattleStrategy {
+        self.strategy.as_ref()
+    }
+
+    pub fn get_build(&self) -> &PokemonBuild {
+        &self.build
     }
 
     pub fn get_name(&self) -> &str {
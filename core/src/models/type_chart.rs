@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{PokeFightError, Result};
+
+/// The official 18-type chart (Gen 6 onwards), including immunities.
+const STANDARD_CHART: &str = include_str!("../../data/type_chart.json");
+
+/// Damage multipliers of attacking types against defending types.
+///
+/// Charts are keyed by attacking type, then by defending type. Matchups that
+/// are not listed are neutral, so a chart only needs the interesting cells:
+///
+/// ```toml
+/// [fire]
+/// grass = 2.0
+/// water = 0.5
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TypeChart {
+    matchups: HashMap<String, HashMap<String, f64>>,
+}
+
+impl TypeChart {
+    pub fn standard() -> Self {
+        Self::from_json(STANDARD_CHART).expect("bundled type chart should be valid")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| PokeFightError::ConfigError(format!("Invalid type chart: {}", e)))
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        toml::from_str(toml)
+            .map_err(|e| PokeFightError::ConfigError(format!("Invalid type chart: {}", e)))
+    }
+
+    /// Loads a chart from a `.json` or `.toml` file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json(&contents),
+            Some("toml") => Self::from_toml(&contents),
+            _ => Err(PokeFightError::ConfigError(format!(
+                "Type chart must be a .json or .toml file: {}",
+                path.display()
+            ))),
+        }
+    }
+
+    pub fn get_effectiveness(&self, attacker_type: &str, defender_type: &str) -> f64 {
+        self.matchups
+            .get(attacker_type)
+            .and_then(|row| row.get(defender_type))
+            .copied()
+            .unwrap_or(1.0)
+    }
+}
+
+impl Default for TypeChart {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_chart_matchups() {
+        let chart = TypeChart::standard();
+
+        assert_eq!(chart.get_effectiveness("ground", "electric"), 2.0);
+        assert_eq!(chart.get_effectiveness("fighting", "normal"), 2.0);
+        assert_eq!(chart.get_effectiveness("fire", "water"), 0.5);
+        assert_eq!(chart.get_effectiveness("normal", "normal"), 1.0);
+    }
+
+    #[test]
+    fn test_standard_chart_immunities() {
+        let chart = TypeChart::standard();
+
+        for (attacker, defender) in [
+            ("normal", "ghost"),
+            ("ghost", "normal"),
+            ("electric", "ground"),
+            ("ground", "flying"),
+            ("fighting", "ghost"),
+            ("poison", "steel"),
+            ("psychic", "dark"),
+            ("dragon", "fairy"),
+        ] {
+            assert_eq!(
+                chart.get_effectiveness(attacker, defender),
+                0.0,
+                "{attacker} should not affect {defender}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_custom_charts() {
+        let json = TypeChart::from_json(r#"{ "fire": { "water": 3.0 } }"#).unwrap();
+        assert_eq!(json.get_effectiveness("fire", "water"), 3.0);
+        assert_eq!(json.get_effectiveness("fire", "grass"), 1.0);
+
+        let toml = TypeChart::from_toml("[ghost]\nnormal = 1.5\n").unwrap();
+        assert_eq!(toml.get_effectiveness("ghost", "normal"), 1.5);
+
+        assert!(TypeChart::from_json("[]").is_err());
+    }
+}
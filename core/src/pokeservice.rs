@@ -1,28 +1,81 @@
-use crate::errors::Result;
-use crate::models::pokemon::Pokemon;
-use reqwest::Client;
+use async_trait::async_trait;
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
 
-pub struct PokeService {
-    client: Client,
-}
+use crate::errors::{PokeFightError, Result};
+use crate::models::pokemon::{Pokemon, PokemonChoice};
+use batch::BatchOptions;
+
+pub mod batch;
+pub mod cache;
+pub mod fixture;
+pub mod http;
+pub mod offline;
+pub mod pool;
+
+/// Anything that can hand out Pokemon for a tournament.
+///
+/// The live PokeAPI client is one implementation; fixture-backed sources can
+/// be used wherever the network is unavailable or undesirable (tests, CI).
+#[async_trait]
+pub trait PokemonSource: Send + Sync {
+    /// Highest national Pokedex number this source can serve.
+    fn max_id(&self) -> u32;
 
-impl PokeService {
-    pub async fn get_random_pokemon(&self) -> Result<Pokemon> {
-        let rand_id = rand::random_range(1..900);
-        let pokemon = self
-            .client
-            .get(format!("https://pokeapi.co/api/v2/pokemon/{rand_id}"))
-            .send()
-            .await?
-            .json::<Pokemon>()
-            .await?;
-        Ok(pokemon)
+    /// Draws with `rng`, so a seeded generator picks the same Pokemon every time.
+    async fn random(&self, rng: &mut (dyn RngCore + Send)) -> Result<Pokemon> {
+        let rand_id = rng.random_range(1..=self.max_id());
+        self.by_id(rand_id).await
+    }
+
+    async fn by_id(&self, id: u32) -> Result<Pokemon>;
+
+    async fn by_name(&self, name: &str) -> Result<Pokemon>;
+
+    /// Numbers past `max_id` are still looked up, so a source whose dex lags
+    /// behind reports them as not found rather than invalid.
+    async fn resolve(
+        &self,
+        choice: &PokemonChoice,
+        rng: &mut (dyn RngCore + Send),
+    ) -> Result<Pokemon> {
+        match choice {
+            PokemonChoice::Random => self.random(rng).await,
+            PokemonChoice::Id(0) => Err(PokeFightError::InvalidPokemonId(0)),
+            PokemonChoice::Id(id) => self.by_id(*id).await,
+            PokemonChoice::Name(name) => self.by_name(name).await,
+        }
+    }
+
+    /// Resolves every choice concurrently, retrying transient failures.
+    ///
+    /// Each choice gets its own generator seeded from `rng` up front, so the
+    /// random draws don't depend on the order the requests complete in.
+    async fn resolve_batch(
+        &self,
+        choices: Vec<PokemonChoice>,
+        options: &BatchOptions,
+        rng: &mut (dyn RngCore + Send),
+    ) -> Vec<Result<Pokemon>> {
+        let seeded: Vec<(PokemonChoice, u64)> = choices
+            .into_iter()
+            .map(|choice| (choice, rng.random()))
+            .collect();
+        batch::fetch_all(seeded, options, |(choice, seed)| async move {
+            self.resolve(&choice, &mut StdRng::seed_from_u64(seed))
+                .await
+        })
+        .await
     }
-}
 
-impl Default for PokeService {
-    fn default() -> Self {
-        let client = reqwest::Client::new();
-        PokeService { client }
+    /// Draws `count` random Pokemon concurrently, retrying transient failures.
+    async fn random_batch(
+        &self,
+        count: usize,
+        options: &BatchOptions,
+        rng: &mut (dyn RngCore + Send),
+    ) -> Vec<Result<Pokemon>> {
+        self.resolve_batch(vec![PokemonChoice::Random; count], options, rng)
+            .await
     }
 }
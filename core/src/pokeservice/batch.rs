@@ -0,0 +1,198 @@
+use std::future::Future;
+use std::time::Duration;
+
+use futures::stream::{self, StreamExt};
+
+use crate::errors::Result;
+
+/// How many requests a batch may run at once and how transient failures
+/// are retried.
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    pub concurrency: usize,
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// How long a single PokeAPI request may take. A request that times out
+    /// is retried like a server error.
+    pub request_timeout: Duration,
+}
+
+impl BatchOptions {
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    /// Exponential backoff: `base_delay * 2^attempt`, capped at `max_delay`.
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay)
+    }
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 8,
+            max_retries: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+            request_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Runs `fetch` for every item with at most `options.concurrency` in flight.
+///
+/// Results are returned in input order, one per item, so a single failure
+/// does not discard the rest of the batch.
+pub async fn fetch_all<T, R, F, Fut>(
+    items: Vec<T>,
+    options: &BatchOptions,
+    fetch: F,
+) -> Vec<Result<R>>
+where
+    T: Clone,
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = Result<R>>,
+{
+    let fetch = &fetch;
+    stream::iter(items)
+        .map(|item| async move { with_retries(options, || fetch(item.clone())).await })
+        .buffered(options.concurrency.max(1))
+        .collect()
+        .await
+}
+
+async fn with_retries<R, F, Fut>(options: &BatchOptions, fetch: F) -> Result<R>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<R>>,
+{
+    let mut attempt = 0;
+    loop {
+        match fetch().await {
+            Err(e) if e.is_transient() && attempt < options.max_retries => {
+                tokio::time::sleep(options.backoff(attempt)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::PokeFightError;
+    use crate::pokeservice::http::PokeService;
+    use crate::pokeservice::PokemonSource;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn fast_options() -> BatchOptions {
+        BatchOptions {
+            base_delay: Duration::from_millis(1),
+            ..BatchOptions::default()
+        }
+    }
+
+    fn pokemon_json(id: u32, name: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "name": name,
+            "stats": [{ "base_stat": 35, "stat": { "name": "hp" } }],
+            "types": [{ "type": { "name": "electric" } }]
+        })
+    }
+
+    #[test]
+    fn test_backoff_is_exponential_and_capped() {
+        let options = BatchOptions {
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+            ..BatchOptions::default()
+        };
+        assert_eq!(options.backoff(0), Duration::from_millis(100));
+        assert_eq!(options.backoff(2), Duration::from_millis(400));
+        assert_eq!(options.backoff(5), Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_are_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/pokemon/25"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/pokemon/25"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(pokemon_json(25, "pikachu")))
+            .mount(&server)
+            .await;
+
+        let service = PokeService::new(&server.uri());
+        let results = fetch_all(vec![25], &fast_options(), |id| service.by_id(id)).await;
+
+        assert_eq!(results[0].as_ref().unwrap().get_name(), "pikachu");
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_requests_are_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/pokemon/25"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(pokemon_json(25, "pikachu"))
+                    .set_delay(Duration::from_secs(5)),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/pokemon/25"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(pokemon_json(25, "pikachu")))
+            .mount(&server)
+            .await;
+
+        let options = fast_options().with_request_timeout(Duration::from_millis(100));
+        let service = PokeService::new(&server.uri()).with_timeout(options.request_timeout);
+        let results = fetch_all(vec![25], &options, |id| service.by_id(id)).await;
+
+        assert_eq!(results[0].as_ref().unwrap().get_name(), "pikachu");
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_batch_keeps_per_item_errors_in_order() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/pokemon/1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(pokemon_json(1, "bulbasaur")))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/pokemon/2"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let service = PokeService::new(&server.uri());
+        let results = fetch_all(vec![1, 2], &fast_options(), |id| service.by_id(id)).await;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap().get_name(), "bulbasaur");
+        assert!(matches!(results[1], Err(PokeFightError::PokeApiError(_))));
+        assert_eq!(server.received_requests().await.unwrap().len(), 1 + 4);
+    }
+}
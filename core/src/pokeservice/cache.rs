@@ -0,0 +1,456 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use super::PokemonSource;
+use crate::errors::{PokeFightError, Result};
+use crate::models::pokemon::Pokemon;
+
+const DEFAULT_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+const DEFAULT_MAX_ENTRIES: usize = 2048;
+
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    /// Directory holding the cache entries.
+    pub dir: PathBuf,
+    /// Entries older than this are re-fetched. `None` keeps them forever.
+    pub ttl: Option<Duration>,
+    /// The oldest entries are evicted once more than this many are stored,
+    /// a tenth more than needed so the next writes don't evict again.
+    pub max_entries: Option<usize>,
+    /// Never fall through to the wrapped source; stale entries are still served.
+    pub cache_only: bool,
+}
+
+impl CacheConfig {
+    /// `$XDG_CACHE_HOME/pokefight` (or the platform equivalent).
+    pub fn default_dir() -> PathBuf {
+        dirs::cache_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("pokefight")
+    }
+
+    pub fn with_cache_only(mut self, cache_only: bool) -> Self {
+        self.cache_only = cache_only;
+        self
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            dir: Self::default_dir(),
+            ttl: Some(DEFAULT_TTL),
+            max_entries: Some(DEFAULT_MAX_ENTRIES),
+            cache_only: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub expired: usize,
+    pub size_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    fetched_at: u64,
+    pokemon: Pokemon,
+}
+
+/// Wraps another source and persists every Pokemon it returns on disk,
+/// keyed by Pokedex number. A small file per name points to the number, so
+/// lookups by name read two files however large the cache grows.
+pub struct CachedSource<S> {
+    inner: S,
+    config: CacheConfig,
+    /// Entries on disk, counted on the first write so that later writes
+    /// don't have to list the cache directory.
+    entry_count: Mutex<Option<usize>>,
+}
+
+impl<S: PokemonSource> CachedSource<S> {
+    pub fn new(inner: S, config: CacheConfig) -> Self {
+        Self {
+            inner,
+            config,
+            entry_count: Mutex::new(None),
+        }
+    }
+
+    pub fn config(&self) -> &CacheConfig {
+        &self.config
+    }
+
+    pub async fn stats(&self) -> Result<CacheStats> {
+        let mut stats = CacheStats::default();
+        for path in self.entry_paths().await? {
+            stats.entries += 1;
+            stats.size_bytes += tokio::fs::metadata(&path).await?.len();
+            if let Some(entry) = read_entry(&path).await
+                && !self.is_fresh(&entry)
+            {
+                stats.expired += 1;
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Removes every entry and returns how many were deleted.
+    pub async fn clear(&self) -> Result<usize> {
+        let paths = self.entry_paths().await?;
+        for path in &paths {
+            tokio::fs::remove_file(path).await?;
+        }
+        match tokio::fs::remove_dir_all(self.names_dir()).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        *self.entry_count.lock().unwrap() = Some(0);
+        Ok(paths.len())
+    }
+
+    fn entries_dir(&self) -> PathBuf {
+        self.config.dir.join("pokemon")
+    }
+
+    fn entry_path(&self, id: u32) -> PathBuf {
+        self.entries_dir().join(format!("{id}.json"))
+    }
+
+    fn names_dir(&self) -> PathBuf {
+        self.config.dir.join("names")
+    }
+
+    /// File holding the Pokedex number of `name`, or `None` for names that
+    /// are not safe as a file name. PokeAPI names are like `mr-mime`.
+    fn name_path(&self, name: &str) -> Option<PathBuf> {
+        let name = name.to_ascii_lowercase();
+        let safe = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        safe.then(|| self.names_dir().join(name))
+    }
+
+    /// The entry of the Pokemon called `name`, found through its name file.
+    async fn read_named(&self, name: &str) -> Option<CacheEntry> {
+        let contents = tokio::fs::read_to_string(self.name_path(name)?)
+            .await
+            .ok()?;
+        let id = contents.trim().parse().ok()?;
+        let entry = read_entry(&self.entry_path(id)).await?;
+        entry
+            .pokemon
+            .get_name()
+            .eq_ignore_ascii_case(name)
+            .then_some(entry)
+    }
+
+    async fn entry_paths(&self) -> Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        let mut dir = match tokio::fs::read_dir(self.entries_dir()).await {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(paths),
+            Err(e) => return Err(e.into()),
+        };
+        while let Some(entry) = dir.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                paths.push(path);
+            }
+        }
+        Ok(paths)
+    }
+
+    fn is_fresh(&self, entry: &CacheEntry) -> bool {
+        match self.config.ttl {
+            Some(ttl) => now_secs().saturating_sub(entry.fetched_at) < ttl.as_secs(),
+            None => true,
+        }
+    }
+
+    fn usable(&self, entry: &CacheEntry) -> bool {
+        self.config.cache_only || self.is_fresh(entry)
+    }
+
+    async fn store(&self, pokemon: &Pokemon) -> Result<()> {
+        tokio::fs::create_dir_all(self.entries_dir()).await?;
+        let entry = CacheEntry {
+            fetched_at: now_secs(),
+            pokemon: pokemon.clone(),
+        };
+        let json = serde_json::to_vec(&entry).map_err(std::io::Error::other)?;
+        let path = self.entry_path(pokemon.id);
+        let is_new = !tokio::fs::try_exists(&path).await.unwrap_or(false);
+        tokio::fs::write(&path, json).await?;
+        if let Some(name_path) = self.name_path(pokemon.get_name()) {
+            tokio::fs::create_dir_all(self.names_dir()).await?;
+            tokio::fs::write(name_path, pokemon.id.to_string()).await?;
+        }
+        if is_new && self.count_new_entry().await? {
+            self.evict().await?;
+        }
+        Ok(())
+    }
+
+    /// Counts a newly written entry, returning whether the cache is now over
+    /// its size limit.
+    async fn count_new_entry(&self) -> Result<bool> {
+        let Some(max_entries) = self.config.max_entries else {
+            return Ok(false);
+        };
+        let known = *self.entry_count.lock().unwrap();
+        let count = match known {
+            Some(count) => count + 1,
+            None => self.entry_paths().await?.len(),
+        };
+        *self.entry_count.lock().unwrap() = Some(count);
+        Ok(count > max_entries)
+    }
+
+    async fn evict(&self) -> Result<()> {
+        let Some(max_entries) = self.config.max_entries else {
+            return Ok(());
+        };
+        let paths = self.entry_paths().await?;
+        let keep = max_entries - max_entries / 10;
+        *self.entry_count.lock().unwrap() = Some(paths.len().min(keep));
+        if paths.len() <= keep {
+            return Ok(());
+        }
+
+        let mut aged = Vec::with_capacity(paths.len());
+        for path in paths {
+            let modified = tokio::fs::metadata(&path).await?.modified()?;
+            aged.push((modified, path));
+        }
+        aged.sort();
+
+        let excess = aged.len() - keep;
+        for (_, path) in aged.into_iter().take(excess) {
+            tokio::fs::remove_file(path).await?;
+        }
+        Ok(())
+    }
+
+    async fn fetched(&self, pokemon: Pokemon) -> Result<Pokemon> {
+        // The cache is best-effort: a read-only cache dir must not break a tournament
+        self.store(&pokemon).await.ok();
+        Ok(pokemon)
+    }
+}
+
+#[async_trait]
+impl<S: PokemonSource> PokemonSource for CachedSource<S> {
+    fn max_id(&self) -> u32 {
+        self.inner.max_id()
+    }
+
+    async fn random(&self, rng: &mut (dyn RngCore + Send)) -> Result<Pokemon> {
+        if !self.config.cache_only {
+            let rand_id = rng.random_range(1..=self.max_id());
+            return self.by_id(rand_id).await;
+        }
+
+        let mut paths = self.entry_paths().await?;
+        if paths.is_empty() {
+            return Err(PokeFightError::NotCached("any".to_string()));
+        }
+        // Directory order varies between runs, so sort before the seeded pick
+        paths.sort();
+        let path = &paths[rng.random_range(0..paths.len())];
+        read_entry(path)
+            .await
+            .map(|entry| entry.pokemon)
+            .ok_or_else(|| PokeFightError::NotCached(path.display().to_string()))
+    }
+
+    async fn by_id(&self, id: u32) -> Result<Pokemon> {
+        if let Some(entry) = read_entry(&self.entry_path(id)).await
+            && self.usable(&entry)
+        {
+            return Ok(entry.pokemon);
+        }
+        if self.config.cache_only {
+            return Err(PokeFightError::NotCached(id.to_string()));
+        }
+        let pokemon = self.inner.by_id(id).await?;
+        self.fetched(pokemon).await
+    }
+
+    async fn by_name(&self, name: &str) -> Result<Pokemon> {
+        if let Some(entry) = self.read_named(name).await
+            && self.usable(&entry)
+        {
+            return Ok(entry.pokemon);
+        }
+        if self.config.cache_only {
+            // Entries cached before names got their own files are only found
+            // by reading them all, which beats failing without a source
+            for path in self.entry_paths().await? {
+                if let Some(entry) = read_entry(&path).await
+                    && entry.pokemon.get_name().eq_ignore_ascii_case(name)
+                {
+                    return Ok(entry.pokemon);
+                }
+            }
+            return Err(PokeFightError::NotCached(name.to_string()));
+        }
+        let pokemon = self.inner.by_name(name).await?;
+        self.fetched(pokemon).await
+    }
+}
+
+async fn read_entry(path: &Path) -> Option<CacheEntry> {
+    let bytes = tokio::fs::read(path).await.ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_utils::create_test_pokemon;
+    use crate::pokeservice::fixture::FixtureSource;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn fixtures() -> FixtureSource {
+        let pokemon = (1..=5)
+            .map(|id| {
+                let mut p = create_test_pokemon(&format!("mon{id}"), 50, 50, 50, 50, "normal");
+                p.id = id;
+                p
+            })
+            .collect();
+        FixtureSource::new(pokemon)
+    }
+
+    fn config(dir: &Path) -> CacheConfig {
+        CacheConfig {
+            dir: dir.to_path_buf(),
+            ..CacheConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_entries_are_served_without_inner_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let warm = CachedSource::new(fixtures(), config(dir.path()));
+        warm.by_id(3).await.unwrap();
+
+        let cold = CachedSource::new(
+            FixtureSource::new(vec![]),
+            config(dir.path()).with_cache_only(true),
+        );
+        assert_eq!(cold.by_id(3).await.unwrap().get_name(), "mon3");
+        assert_eq!(cold.by_name("MON3").await.unwrap().id, 3);
+        assert_eq!(
+            cold.random(&mut StdRng::seed_from_u64(1)).await.unwrap().id,
+            3
+        );
+        assert!(matches!(
+            cold.by_id(4).await,
+            Err(PokeFightError::NotCached(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_expired_entries_are_refetched() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = CachedSource::new(
+            fixtures(),
+            CacheConfig {
+                ttl: Some(Duration::ZERO),
+                ..config(dir.path())
+            },
+        );
+        source.by_id(1).await.unwrap();
+
+        let stats = source.stats().await.unwrap();
+        assert_eq!(stats.entries, 1);
+        assert_eq!(stats.expired, 1);
+    }
+
+    #[tokio::test]
+    async fn test_size_limit_evicts_and_clear_empties() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = CachedSource::new(
+            fixtures(),
+            CacheConfig {
+                max_entries: Some(2),
+                ..config(dir.path())
+            },
+        );
+        for id in 1..=5 {
+            source.by_id(id).await.unwrap();
+        }
+        assert_eq!(source.stats().await.unwrap().entries, 2);
+
+        assert_eq!(source.clear().await.unwrap(), 2);
+        assert_eq!(source.stats().await.unwrap().entries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_names_point_to_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = CachedSource::new(fixtures(), config(dir.path()));
+        source.by_name("Mon2").await.unwrap();
+        source.by_id(4).await.unwrap();
+
+        let name_file = dir.path().join("names").join("mon4");
+        assert_eq!(std::fs::read_to_string(&name_file).unwrap(), "4");
+
+        // A name pointing at an evicted entry falls through to the source
+        std::fs::remove_file(dir.path().join("pokemon").join("4.json")).unwrap();
+        let cold = CachedSource::new(
+            FixtureSource::new(vec![]),
+            config(dir.path()).with_cache_only(true),
+        );
+        assert_eq!(cold.by_name("mon2").await.unwrap().id, 2);
+        assert!(matches!(
+            cold.by_name("mon4").await,
+            Err(PokeFightError::NotCached(_))
+        ));
+        assert!(source.by_name("mon4").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_eviction_leaves_room_for_new_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let pokemon = (1..=12)
+            .map(|id| {
+                let mut p = create_test_pokemon(&format!("mon{id}"), 50, 50, 50, 50, "normal");
+                p.id = id;
+                p
+            })
+            .collect();
+        let source = CachedSource::new(
+            FixtureSource::new(pokemon),
+            CacheConfig {
+                max_entries: Some(10),
+                ..config(dir.path())
+            },
+        );
+        for id in 1..=10 {
+            source.by_id(id).await.unwrap();
+        }
+        assert_eq!(source.stats().await.unwrap().entries, 10);
+
+        // Crossing the limit evicts down to nine, so the next write fits
+        source.by_id(11).await.unwrap();
+        assert_eq!(source.stats().await.unwrap().entries, 9);
+        source.by_id(12).await.unwrap();
+        assert_eq!(source.stats().await.unwrap().entries, 10);
+    }
+}
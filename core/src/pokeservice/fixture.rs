@@ -0,0 +1,112 @@
+use async_trait::async_trait;
+use rand::{Rng, RngCore};
+
+use super::PokemonSource;
+use crate::errors::{PokeFightError, Result};
+use crate::models::pokemon::Pokemon;
+
+/// An in-memory source backed by a fixed list of Pokemon.
+pub struct FixtureSource {
+    pokemon: Vec<Pokemon>,
+}
+
+impl FixtureSource {
+    pub fn new(pokemon: Vec<Pokemon>) -> Self {
+        Self { pokemon }
+    }
+
+    /// Loads fixtures from a JSON array in the PokeAPI `pokemon` shape.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let pokemon = serde_json::from_str(json).map_err(|e| {
+            PokeFightError::TournamentError(format!("Invalid Pokemon fixtures: {}", e))
+        })?;
+        Ok(Self::new(pokemon))
+    }
+}
+
+#[async_trait]
+impl PokemonSource for FixtureSource {
+    fn max_id(&self) -> u32 {
+        self.pokemon.iter().map(|p| p.id).max().unwrap_or(0)
+    }
+
+    async fn random(&self, rng: &mut (dyn RngCore + Send)) -> Result<Pokemon> {
+        if self.pokemon.is_empty() {
+            return Err(PokeFightError::TournamentError(
+                "Fixture source is empty".to_string(),
+            ));
+        }
+        let index = rng.random_range(0..self.pokemon.len());
+        Ok(self.pokemon[index].clone())
+    }
+
+    async fn by_id(&self, id: u32) -> Result<Pokemon> {
+        self.pokemon
+            .iter()
+            .find(|p| p.id == id)
+            .cloned()
+            .ok_or(PokeFightError::PokemonNotFound(id))
+    }
+
+    async fn by_name(&self, name: &str) -> Result<Pokemon> {
+        self.pokemon
+            .iter()
+            .find(|p| p.get_name().eq_ignore_ascii_case(name))
+            .cloned()
+            .ok_or_else(|| PokeFightError::PokemonNameNotFound(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::pokemon::PokemonChoice;
+    use crate::models::test_utils::create_test_pokemon;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn fixtures() -> FixtureSource {
+        let mut pikachu = create_test_pokemon("pikachu", 35, 55, 40, 90, "electric");
+        pikachu.id = 25;
+        let mut eevee = create_test_pokemon("eevee", 55, 55, 50, 55, "normal");
+        eevee.id = 133;
+        FixtureSource::new(vec![pikachu, eevee])
+    }
+
+    #[tokio::test]
+    async fn test_lookup_by_id_and_name() {
+        let source = fixtures();
+
+        assert_eq!(source.by_id(133).await.unwrap().get_name(), "eevee");
+        assert_eq!(source.by_name("Pikachu").await.unwrap().id, 25);
+        assert!(matches!(
+            source.by_id(1).await,
+            Err(PokeFightError::PokemonNotFound(1))
+        ));
+        assert!(source.by_name("mew").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_asks_the_source_past_max_id() {
+        let source = fixtures();
+
+        assert!(matches!(
+            source
+                .resolve(&PokemonChoice::Id(200), &mut StdRng::seed_from_u64(1))
+                .await,
+            Err(PokeFightError::PokemonNotFound(200))
+        ));
+        assert!(matches!(
+            source
+                .resolve(&PokemonChoice::Id(0), &mut StdRng::seed_from_u64(1))
+                .await,
+            Err(PokeFightError::InvalidPokemonId(0))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_empty_source_has_no_random_pokemon() {
+        let source = FixtureSource::new(vec![]);
+        assert!(source.random(&mut StdRng::seed_from_u64(1)).await.is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode};
+
+use super::batch::BatchOptions;
+use super::PokemonSource;
+use crate::errors::{PokeFightError, Result};
+use crate::models::pokemon::Pokemon;
+
+pub const POKEAPI_BASE_URL: &str = "https://pokeapi.co/api/v2";
+pub(crate) const MAX_POKEMON_ID: u32 = 1025;
+
+pub struct PokeService {
+    client: Client,
+    base_url: String,
+}
+
+impl PokeService {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            client: client(BatchOptions::default().request_timeout),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Gives up on requests PokeAPI does not answer within `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.client = client(timeout);
+        self
+    }
+
+    /// Returns `None` when PokeAPI has no Pokemon under `key`.
+    async fn fetch_pokemon(&self, key: &str) -> Result<Option<Pokemon>> {
+        let response = self
+            .client
+            .get(format!("{}/pokemon/{key}", self.base_url))
+            .send()
+            .await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let pokemon = response.error_for_status()?.json::<Pokemon>().await?;
+        Ok(Some(pokemon))
+    }
+}
+
+fn client(timeout: Duration) -> Client {
+    Client::builder()
+        .timeout(timeout)
+        .build()
+        .expect("HTTP client should build")
+}
+
+#[async_trait]
+impl PokemonSource for PokeService {
+    fn max_id(&self) -> u32 {
+        MAX_POKEMON_ID
+    }
+
+    async fn by_id(&self, id: u32) -> Result<Pokemon> {
+        self.fetch_pokemon(&id.to_string())
+            .await?
+            .ok_or(PokeFightError::PokemonNotFound(id))
+    }
+
+    async fn by_name(&self, name: &str) -> Result<Pokemon> {
+        self.fetch_pokemon(&name.to_lowercase())
+            .await?
+            .ok_or_else(|| PokeFightError::PokemonNameNotFound(name.to_string()))
+    }
+}
+
+impl Default for PokeService {
+    fn default() -> Self {
+        PokeService::new(POKEAPI_BASE_URL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::pokemon::PokemonChoice;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_unknown_pokemon_maps_to_not_found() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        let service = PokeService::new(&server.uri());
+
+        assert!(matches!(
+            service.by_id(42).await,
+            Err(PokeFightError::PokemonNotFound(42))
+        ));
+        assert!(matches!(
+            service.by_name("missingno").await,
+            Err(PokeFightError::PokemonNameNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_choice() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/pokemon/mr-mime"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 122,
+                "name": "mr-mime",
+                "stats": [],
+                "types": []
+            })))
+            .mount(&server)
+            .await;
+        let service = PokeService::new(&server.uri());
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let choice: PokemonChoice = "Mr. Mime".parse().unwrap();
+        assert_eq!(service.resolve(&choice, &mut rng).await.unwrap().id, 122);
+        assert!(matches!(
+            service.resolve(&PokemonChoice::Id(0), &mut rng).await,
+            Err(PokeFightError::InvalidPokemonId(0))
+        ));
+    }
+}
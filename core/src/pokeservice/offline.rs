@@ -0,0 +1,122 @@
+use async_trait::async_trait;
+use rand::RngCore;
+
+use super::fixture::FixtureSource;
+use super::PokemonSource;
+use crate::errors::Result;
+use crate::models::pokemon::Pokemon;
+
+/// The bundled Pokedex, stored in the same shape PokeAPI returns. It covers
+/// the national dex in each species' default form.
+const BUNDLED_POKEDEX: &str = include_str!("../../data/pokedex.json");
+
+/// A source that serves Pokemon from the bundled dataset without any HTTP.
+///
+/// Species missing from the dataset are reported as not found.
+pub struct OfflineSource {
+    pokedex: FixtureSource,
+}
+
+impl OfflineSource {
+    pub fn new() -> Self {
+        let pokedex =
+            FixtureSource::from_json(BUNDLED_POKEDEX).expect("bundled Pokedex should be valid");
+        Self { pokedex }
+    }
+}
+
+impl Default for OfflineSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl PokemonSource for OfflineSource {
+    fn max_id(&self) -> u32 {
+        self.pokedex.max_id()
+    }
+
+    async fn random(&self, rng: &mut (dyn RngCore + Send)) -> Result<Pokemon> {
+        self.pokedex.random(rng).await
+    }
+
+    async fn by_id(&self, id: u32) -> Result<Pokemon> {
+        self.pokedex.by_id(id).await
+    }
+
+    async fn by_name(&self, name: &str) -> Result<Pokemon> {
+        self.pokedex.by_name(name).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::PokeFightError;
+    use crate::pokeservice::batch::BatchOptions;
+    use crate::pokeservice::http::MAX_POKEMON_ID;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[tokio::test]
+    async fn test_bundled_pokedex_is_complete() {
+        let source = OfflineSource::new();
+
+        assert_eq!(source.max_id(), MAX_POKEMON_ID);
+        for id in 1..=source.max_id() {
+            let pokemon = source.by_id(id).await.unwrap();
+            assert_eq!(pokemon.stats.len(), 6, "{} has missing stats", pokemon.name);
+            assert!(!pokemon.types.is_empty(), "{} has no types", pokemon.name);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bundled_pokedex_stats() {
+        let source = OfflineSource::new();
+        let charizard = source.by_name("charizard").await.unwrap();
+
+        assert_eq!(charizard.id, 6);
+        assert_eq!(charizard.get_hp(), 78);
+        assert_eq!(charizard.get_speed(), 100);
+        assert_eq!(charizard.types.len(), 2);
+
+        let garchomp = source.by_id(445).await.unwrap();
+        assert_eq!(garchomp.name, "garchomp");
+        assert_eq!(garchomp.get_attack(), 130);
+    }
+
+    #[tokio::test]
+    async fn test_species_outside_bundled_pokedex_are_not_found() {
+        let source = OfflineSource::new();
+
+        assert!(matches!(
+            source.by_id(source.max_id() + 1).await,
+            Err(PokeFightError::PokemonNotFound(1026))
+        ));
+        assert!(source.by_name("missingno").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_seeded_random_batch_is_reproducible() {
+        let source = OfflineSource::new();
+        let draw = |seed| {
+            let source = &source;
+            async move {
+                source
+                    .random_batch(
+                        6,
+                        &BatchOptions::default(),
+                        &mut StdRng::seed_from_u64(seed),
+                    )
+                    .await
+                    .into_iter()
+                    .map(|result| result.unwrap().id)
+                    .collect::<Vec<u32>>()
+            }
+        };
+
+        assert_eq!(draw(3).await, draw(3).await);
+        assert_ne!(draw(3).await, draw(4).await);
+    }
+}
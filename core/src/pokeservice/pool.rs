@@ -0,0 +1,306 @@
+use std::collections::HashSet;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use super::batch::{self, BatchOptions};
+use super::PokemonSource;
+use crate::errors::{PokeFightError, Result};
+use crate::models::pokemon::{Pokemon, PokemonChoice};
+
+/// Last national Pokedex number of each generation.
+const GENERATION_ENDS: [u32; 9] = [151, 251, 386, 493, 649, 721, 809, 905, 1025];
+
+/// National Pokedex numbers of every legendary and mythical Pokemon.
+const LEGENDARY_AND_MYTHICAL_IDS: &[u32] = &[
+    144, 145, 146, 150, 151, // Kanto
+    243, 244, 245, 249, 250, 251, // Johto
+    377, 378, 379, 380, 381, 382, 383, 384, 385, 386, // Hoenn
+    480, 481, 482, 483, 484, 485, 486, 487, 488, 489, 490, 491, 492, 493, // Sinnoh
+    494, 638, 639, 640, 641, 642, 643, 644, 645, 646, 647, 648, 649, // Unova
+    716, 717, 718, 719, 720, 721, // Kalos
+    772, 773, 785, 786, 787, 788, 789, 790, 791, 792, 800, 801, 802, 807, 808, 809, // Alola
+    888, 889, 890, 891, 892, 893, 894, 895, 896, 897, 898, 905, // Galar & Hisui
+    1001, 1002, 1003, 1004, 1007, 1008, 1014, 1015, 1016, 1017, 1024, 1025, // Paldea
+];
+
+/// How many candidates are tried per slot before giving up on a sparse pool.
+const MAX_ATTEMPTS_PER_SLOT: usize = 50;
+
+/// Restricts which Pokemon may be drawn for a tournament.
+///
+/// Every field is optional; the default pool allows everything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DraftPool {
+    /// Allowed generations (1-9). Empty allows all.
+    pub generations: Vec<u8>,
+    /// Allowed types; a Pokemon qualifies if any of its types is listed. Empty allows all.
+    pub types: Vec<String>,
+    pub exclude_legendaries: bool,
+    pub max_base_stat_total: Option<i64>,
+    /// No species may appear twice in the same tournament.
+    pub unique_species: bool,
+}
+
+pub fn generation_of(id: u32) -> Option<u8> {
+    if id == 0 {
+        return None;
+    }
+    GENERATION_ENDS
+        .iter()
+        .position(|&end| id <= end)
+        .map(|index| index as u8 + 1)
+}
+
+pub fn is_legendary(id: u32) -> bool {
+    LEGENDARY_AND_MYTHICAL_IDS.contains(&id)
+}
+
+impl DraftPool {
+    /// Checks a fetched Pokemon against every filter.
+    pub fn allows(&self, pokemon: &Pokemon) -> bool {
+        self.allows_id(pokemon.id)
+            && (self.types.is_empty()
+                || pokemon.types.iter().any(|t| {
+                    self.types
+                        .iter()
+                        .any(|a| a.eq_ignore_ascii_case(&t.type_info.name))
+                }))
+            && self
+                .max_base_stat_total
+                .is_none_or(|max| pokemon.get_base_stat_total() <= max)
+    }
+
+    /// The filters that can be decided from the Pokedex number alone.
+    fn allows_id(&self, id: u32) -> bool {
+        let generation_ok = self.generations.is_empty()
+            || generation_of(id).is_some_and(|g| self.generations.contains(&g));
+        generation_ok && !(self.exclude_legendaries && is_legendary(id))
+    }
+
+    fn candidate_ids(&self, max_id: u32) -> Vec<u32> {
+        (1..=max_id).filter(|&id| self.allows_id(id)).collect()
+    }
+
+    /// Resolves one Pokemon per choice, drawing the random ones from this pool.
+    ///
+    /// Explicit picks must satisfy the pool too. Results are returned in
+    /// input order, one per choice. Random draws only depend on `rng`, so a
+    /// seeded generator drafts the same Pokemon every time.
+    pub async fn draft(
+        &self,
+        source: &dyn PokemonSource,
+        choices: Vec<PokemonChoice>,
+        options: &BatchOptions,
+        rng: &mut (dyn RngCore + Send),
+    ) -> Vec<Result<Pokemon>> {
+        let mut taken = HashSet::new();
+        let mut results: Vec<Option<Result<Pokemon>>> = Vec::with_capacity(choices.len());
+
+        let picks: Vec<PokemonChoice> = choices
+            .iter()
+            .filter(|c| **c != PokemonChoice::Random)
+            .cloned()
+            .collect();
+        let mut resolved = source.resolve_batch(picks, options, rng).await.into_iter();
+        for choice in &choices {
+            if *choice == PokemonChoice::Random {
+                results.push(None);
+                continue;
+            }
+            let result = resolved
+                .next()
+                .expect("one result per pick")
+                .and_then(|pokemon| {
+                    if !self.allows(&pokemon) {
+                        Err(PokeFightError::PokemonNotInPool(pokemon.name))
+                    } else if self.unique_species && !taken.insert(pokemon.id) {
+                        Err(PokeFightError::DuplicatePokemon(pokemon.name))
+                    } else {
+                        Ok(pokemon)
+                    }
+                });
+            results.push(Some(result));
+        }
+
+        let needed = results.iter().filter(|r| r.is_none()).count();
+        let mut drawn = self
+            .draw_random(source, needed, &mut taken, options, rng)
+            .await
+            .into_iter();
+        results
+            .into_iter()
+            .map(|r| r.unwrap_or_else(|| drawn.next().expect("one draw per random slot")))
+            .collect()
+    }
+
+    async fn draw_random(
+        &self,
+        source: &dyn PokemonSource,
+        needed: usize,
+        taken: &mut HashSet<u32>,
+        options: &BatchOptions,
+        rng: &mut (dyn RngCore + Send),
+    ) -> Vec<Result<Pokemon>> {
+        let mut candidates = self.candidate_ids(source.max_id());
+        if self.unique_species {
+            candidates.retain(|id| !taken.contains(id));
+        }
+        candidates.shuffle(rng);
+
+        let mut drawn = Vec::with_capacity(needed);
+        let mut attempts_left = needed * MAX_ATTEMPTS_PER_SLOT;
+        while drawn.len() < needed && attempts_left > 0 && !candidates.is_empty() {
+            let wanted = (needed - drawn.len()).min(attempts_left);
+            let ids: Vec<u32> = if self.unique_species {
+                candidates.drain(..wanted.min(candidates.len())).collect()
+            } else {
+                (0..wanted)
+                    .map(|_| candidates[rng.random_range(0..candidates.len())])
+                    .collect()
+            };
+            attempts_left -= ids.len();
+
+            let fetched = batch::fetch_all(ids, options, |id| source.by_id(id)).await;
+            for result in fetched {
+                match result {
+                    Ok(pokemon) if !self.allows(&pokemon) => {}
+                    Ok(pokemon) if self.unique_species && !taken.insert(pokemon.id) => {}
+                    // Gaps in the source (e.g. a cold cache) just reject the candidate
+                    Err(PokeFightError::NotCached(_) | PokeFightError::PokemonNotFound(_)) => {}
+                    other => drawn.push(other),
+                }
+            }
+        }
+
+        while drawn.len() < needed {
+            drawn.push(Err(PokeFightError::PoolExhausted));
+        }
+        drawn
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pokeservice::offline::OfflineSource;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_generation_of() {
+        assert_eq!(generation_of(0), None);
+        assert_eq!(generation_of(151), Some(1));
+        assert_eq!(generation_of(152), Some(2));
+        assert_eq!(generation_of(1025), Some(9));
+        assert_eq!(generation_of(1026), None);
+    }
+
+    #[tokio::test]
+    async fn test_draft_respects_filters() {
+        let source = OfflineSource::new();
+        let pool = DraftPool {
+            types: vec!["fire".into()],
+            exclude_legendaries: true,
+            max_base_stat_total: Some(500),
+            unique_species: true,
+            ..DraftPool::default()
+        };
+
+        let drawn = pool
+            .draft(
+                &source,
+                vec![PokemonChoice::Random; 6],
+                &BatchOptions::default(),
+                &mut StdRng::seed_from_u64(1),
+            )
+            .await;
+
+        let mut seen = HashSet::new();
+        for pokemon in drawn {
+            let pokemon = pokemon.unwrap();
+            assert!(pokemon.get_types().contains(&"fire"));
+            assert!(pokemon.get_base_stat_total() <= 500);
+            assert!(!is_legendary(pokemon.id));
+            assert!(seen.insert(pokemon.id), "{} drawn twice", pokemon.name);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_draft_rejects_picks_outside_pool() {
+        let source = OfflineSource::new();
+        let pool = DraftPool {
+            exclude_legendaries: true,
+            unique_species: true,
+            ..DraftPool::default()
+        };
+        let choices = vec![
+            PokemonChoice::Name("mewtwo".into()),
+            PokemonChoice::Id(25),
+            PokemonChoice::Name("pikachu".into()),
+        ];
+
+        let drawn = pool
+            .draft(
+                &source,
+                choices,
+                &BatchOptions::default(),
+                &mut StdRng::seed_from_u64(1),
+            )
+            .await;
+
+        assert!(matches!(drawn[0], Err(PokeFightError::PokemonNotInPool(_))));
+        assert_eq!(drawn[1].as_ref().unwrap().get_name(), "pikachu");
+        assert!(matches!(drawn[2], Err(PokeFightError::DuplicatePokemon(_))));
+    }
+
+    #[tokio::test]
+    async fn test_draft_reports_exhausted_pool() {
+        let source = OfflineSource::new();
+        let pool = DraftPool {
+            generations: vec![1],
+            types: vec!["dragon".into()],
+            unique_species: true,
+            ..DraftPool::default()
+        };
+
+        let drawn = pool
+            .draft(
+                &source,
+                vec![PokemonChoice::Random; 4],
+                &BatchOptions::default(),
+                &mut StdRng::seed_from_u64(1),
+            )
+            .await;
+
+        // Kanto only has Dratini, Dragonair and Dragonite
+        assert_eq!(drawn.iter().filter(|r| r.is_ok()).count(), 3);
+        assert!(matches!(drawn[3], Err(PokeFightError::PoolExhausted)));
+    }
+
+    #[tokio::test]
+    async fn test_same_seed_drafts_same_pokemon() {
+        let source = OfflineSource::new();
+        let pool = DraftPool::default();
+        let draft = |seed| {
+            let pool = &pool;
+            let source = &source;
+            async move {
+                pool.draft(
+                    source,
+                    vec![PokemonChoice::Random; 5],
+                    &BatchOptions::default(),
+                    &mut StdRng::seed_from_u64(seed),
+                )
+                .await
+                .into_iter()
+                .map(|p| p.unwrap().id)
+                .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(draft(42).await, draft(42).await);
+    }
+}
@@ -1,14 +1,21 @@
 use axum::extract::FromRef;
-use pokefight_core::PokeService;
+use pokefight_core::{PokemonSource, TypeChart};
 use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct AppState {
-    pub poke_service: Arc<PokeService>,
+    pub poke_service: Arc<dyn PokemonSource>,
+    pub type_chart: Arc<TypeChart>,
 }
 
-impl FromRef<AppState> for Arc<PokeService> {
+impl FromRef<AppState> for Arc<dyn PokemonSource> {
     fn from_ref(app_state: &AppState) -> Self {
         app_state.poke_service.clone()
     }
 }
+
+impl FromRef<AppState> for Arc<TypeChart> {
+    fn from_ref(app_state: &AppState) -> Self {
+        app_state.type_chart.clone()
+    }
+}
@@ -1,14 +1,90 @@
-use axum::{extract::State, http::StatusCode, Json, Router};
-use pokefight_core::MatchResult;
+use axum::{
+    extract::{rejection::JsonRejection, State},
+    http::StatusCode,
+    Json, Router,
+};
+use pokefight_core::models::stats::MAX_LEVEL;
+use pokefight_core::models::trainer::MAX_TEAM_SIZE;
+use pokefight_core::{
+    Arena, BattleFormat, DraftPool, MatchResult, PokeFightError, PokemonChoice, Roster,
+    RosterEntry, Ruleset,
+};
 use serde::{Deserialize, Serialize};
 
-use crate::{adapters::http::app_state::AppState, application::tournament::TournamentService};
+use crate::{
+    adapters::http::app_state::AppState,
+    application::tournament::{TournamentService, TournamentSettings},
+};
+
+/// A participant is either a bare name or
+/// `{ "name": ..., "pokemon": ..., "item": ..., "strategy": ... }`, where
+/// `pokemon` is a Pokedex number or a name, `item` a held item like
+/// `leftovers` and `strategy` a battle strategy like `greedy`. In teams,
+/// `pokemon` leads and only the lead holds `item`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ParticipantRequest {
+    Name(String),
+    WithPokemon {
+        name: String,
+        #[serde(default)]
+        pokemon: PokemonChoice,
+        #[serde(default)]
+        item: Option<String>,
+        #[serde(default)]
+        strategy: Option<String>,
+    },
+}
+
+impl From<ParticipantRequest> for RosterEntry {
+    fn from(req: ParticipantRequest) -> Self {
+        match req {
+            ParticipantRequest::Name(name) => RosterEntry {
+                name,
+                pokemon: PokemonChoice::Random,
+                item: None,
+                strategy: None,
+            },
+            ParticipantRequest::WithPokemon {
+                name,
+                pokemon,
+                item,
+                strategy,
+            } => RosterEntry {
+                name,
+                pokemon,
+                item,
+                strategy,
+            },
+        }
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TournamentRequest {
-    names: Vec<String>,
+    names: Vec<ParticipantRequest>,
     chore: String,
     title: String,
+    #[serde(default)]
+    pool: DraftPool,
+    #[serde(default)]
+    ruleset: Ruleset,
+    #[serde(default)]
+    seed: Option<u64>,
+    /// Every Pokemon's level, 1 to 100. Level 50 when absent.
+    #[serde(default)]
+    level: Option<u8>,
+    #[serde(default)]
+    random_builds: bool,
+    /// Weather and terrain, e.g. `{ "weather": "rain", "turns": 5 }`.
+    #[serde(default)]
+    arena: Arena,
+    /// Pokemon per trainer, 1 to 6. Singles when absent.
+    #[serde(default)]
+    team_size: Option<u8>,
+    /// `singles` or `doubles`, singles when absent.
+    #[serde(default)]
+    format: BattleFormat,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,6 +93,9 @@ pub struct TournamentResponse {
     chore: String,
     matches: Vec<MatchResult>,
     champion: String,
+    seed: u64,
+    arena: Arena,
+    format: BattleFormat,
 }
 
 #[derive(Debug, Serialize)]
@@ -27,10 +106,47 @@ pub struct ErrorResponse {
 #[axum::debug_handler]
 pub async fn tournament(
     State(app_state): State<AppState>,
-    Json(req): Json<TournamentRequest>,
+    req: Result<Json<TournamentRequest>, JsonRejection>,
 ) -> Result<(StatusCode, Json<TournamentResponse>), (StatusCode, Json<ErrorResponse>)> {
+    // Malformed requests, e.g. an empty Pokemon pick, get the usual error body
+    let Json(req) = req.map_err(|rejection| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: rejection.body_text(),
+            }),
+        )
+    })?;
+
+    // Reject oversized teams and impossible levels before drafting a single Pokemon
+    let team_size = req.team_size.map_or(1, usize::from);
+    if !(1..=MAX_TEAM_SIZE).contains(&team_size) {
+        return Err(error_response(PokeFightError::InvalidTeamSize(team_size)));
+    }
+    if let Some(level) = req.level
+        && !(1..=MAX_LEVEL).contains(&level)
+    {
+        return Err(error_response(PokeFightError::InvalidLevel(level)));
+    }
+
     let poke_service = app_state.poke_service.clone();
-    match TournamentService::run_tournament(req.names, req.title, req.chore, poke_service).await {
+    let type_chart = app_state.type_chart.clone();
+    let roster = Roster {
+        trainers: req.names.into_iter().map(RosterEntry::from).collect(),
+    };
+    let settings = TournamentSettings {
+        title: req.title,
+        chore: req.chore,
+        pool: req.pool,
+        ruleset: req.ruleset,
+        seed: req.seed,
+        level: req.level,
+        random_builds: req.random_builds,
+        arena: req.arena,
+        team_size,
+        format: req.format,
+    };
+    match TournamentService::run_tournament(roster, settings, poke_service, type_chart).await {
         Ok(result) => Ok((
             StatusCode::OK,
             Json(TournamentResponse {
@@ -38,17 +154,143 @@ pub async fn tournament(
                 chore: result.chore,
                 matches: result.matches,
                 champion: result.champion,
+                seed: result.seed,
+                arena: result.arena,
+                format: result.format,
             }),
         )),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )),
+        Err(e) => Err(error_response(e)),
+    }
+}
+
+fn error_response(error: PokeFightError) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        error_status(&error),
+        Json(ErrorResponse {
+            error: error.to_string(),
+        }),
+    )
+}
+
+fn error_status(error: &PokeFightError) -> StatusCode {
+    match error {
+        PokeFightError::DraftFailed { source, .. } => error_status(source),
+        PokeFightError::PokemonNotFound(_)
+        | PokeFightError::PokemonNameNotFound(_)
+        | PokeFightError::InvalidPokemonId(_)
+        | PokeFightError::PokemonNotInPool(_)
+        | PokeFightError::DuplicatePokemon(_)
+        | PokeFightError::PoolExhausted
+        | PokeFightError::NoParticipants
+        | PokeFightError::InvalidParticipantCount(_)
+        | PokeFightError::UnknownItem(_)
+        | PokeFightError::UnknownStrategy(_)
+        | PokeFightError::InvalidTeamSize(_)
+        | PokeFightError::InvalidLevel(_) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
     }
 }
 
 pub fn router() -> Router<AppState> {
     Router::new().route("/", axum::routing::post(tournament))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pokefight_core::{Pokemon, PokemonSource, Result, TypeChart};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Counts the Pokemon asked for without handing any out.
+    #[derive(Default)]
+    struct CountingSource {
+        fetched: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl PokemonSource for CountingSource {
+        fn max_id(&self) -> u32 {
+            1025
+        }
+
+        async fn by_id(&self, id: u32) -> Result<Pokemon> {
+            self.fetched.fetch_add(1, Ordering::SeqCst);
+            Err(PokeFightError::PokemonNotFound(id))
+        }
+
+        async fn by_name(&self, name: &str) -> Result<Pokemon> {
+            self.fetched.fetch_add(1, Ordering::SeqCst);
+            Err(PokeFightError::PokemonNameNotFound(name.to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_oversized_teams_are_rejected_before_drafting() {
+        let source = Arc::new(CountingSource::default());
+        let app_state = AppState {
+            poke_service: source.clone(),
+            type_chart: Arc::new(TypeChart::standard()),
+        };
+        let req: TournamentRequest = serde_json::from_value(serde_json::json!({
+            "names": ["Ash", "Gary"],
+            "chore": "dishes",
+            "title": "Test Tournament",
+            "team_size": 255
+        }))
+        .unwrap();
+
+        let Err((status, Json(error))) = tournament(State(app_state), Ok(Json(req))).await else {
+            panic!("a team of 255 should be rejected");
+        };
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(error.error.contains("255"), "{}", error.error);
+        assert_eq!(source.fetched.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_out_of_range_levels_are_rejected() {
+        for level in [0, 101, 255] {
+            let source = Arc::new(CountingSource::default());
+            let app_state = AppState {
+                poke_service: source.clone(),
+                type_chart: Arc::new(TypeChart::standard()),
+            };
+            let req: TournamentRequest = serde_json::from_value(serde_json::json!({
+                "names": ["Ash", "Gary"],
+                "chore": "dishes",
+                "title": "Test Tournament",
+                "level": level
+            }))
+            .unwrap();
+
+            let Err((status, Json(error))) = tournament(State(app_state), Ok(Json(req))).await
+            else {
+                panic!("level {level} should be rejected");
+            };
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert!(error.error.contains(&level.to_string()), "{}", error.error);
+            assert_eq!(source.fetched.load(Ordering::SeqCst), 0);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_empty_pokemon_picks_are_rejected() {
+        let source = Arc::new(CountingSource::default());
+        let app_state = AppState {
+            poke_service: source.clone(),
+            type_chart: Arc::new(TypeChart::standard()),
+        };
+        let req = Json::<TournamentRequest>::from_bytes(
+            br#"{ "names": [{ "name": "Ash", "pokemon": " " }, "Gary"],
+                  "chore": "dishes", "title": "Test Tournament" }"#,
+        );
+        assert!(req.is_err());
+
+        let Err((status, _)) = tournament(State(app_state), req).await else {
+            panic!("an empty pick should be rejected");
+        };
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(source.fetched.load(Ordering::SeqCst), 0);
+    }
+}
@@ -1,25 +1,66 @@
-use pokefight_core::{PokeService, Result, Trainer, Tournament, TournamentResult};
+use pokefight_core::{
+    Arena, BatchOptions, BattleFormat, DraftPool, PokemonSource, Result, Roster, Ruleset,
+    Tournament, TournamentResult, TypeChart,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use std::sync::Arc;
 
+pub struct TournamentSettings {
+    pub title: String,
+    pub chore: String,
+    pub pool: DraftPool,
+    pub ruleset: Ruleset,
+    /// Picked at random when absent.
+    pub seed: Option<u64>,
+    /// Every Pokemon's level, 50 when absent.
+    pub level: Option<u8>,
+    /// Random IVs and natures for every Pokemon.
+    pub random_builds: bool,
+    /// Weather and terrain every battle is fought in.
+    pub arena: Arena,
+    /// Pokemon per trainer, 1 for singles. A participant's pick leads its
+    /// team, the rest is drawn at random.
+    pub team_size: usize,
+    /// Singles or doubles.
+    pub format: BattleFormat,
+}
+
 pub struct TournamentService;
 
 impl TournamentService {
     pub async fn run_tournament(
-        names: Vec<String>,
-        title: String,
-        chore: String,
-        poke_service: Arc<PokeService>,
+        roster: Roster,
+        settings: TournamentSettings,
+        poke_service: Arc<dyn PokemonSource>,
+        type_chart: Arc<TypeChart>,
     ) -> Result<TournamentResult> {
-        // Fetch random Pokemon for each participant
-        let mut trainers = Vec::new();
+        let seed = settings.seed.unwrap_or_else(rand::random);
 
-        for name in names {
-            let pokemon = poke_service.get_random_pokemon().await?;
-            trainers.push(Trainer::new(name, pokemon));
-        }
+        let team_size = settings.team_size.max(1);
+        let trainers = roster
+            .draft_trainers(
+                &settings.pool,
+                poke_service.as_ref(),
+                team_size,
+                &BatchOptions::default(),
+                &mut StdRng::seed_from_u64(seed),
+            )
+            .await?;
 
         // Create and start tournament
-        let tournament = Tournament::new(trainers, &title, &chore);
+        let tournament = Tournament::new(trainers, &settings.title, &settings.chore)
+            .with_type_chart(type_chart.as_ref().clone())
+            .with_damage_model(settings.ruleset.damage_model())
+            .with_seed(seed)
+            .with_random_builds(settings.random_builds)
+            .with_team_size(team_size)
+            .with_format(settings.format)
+            .with_arena(settings.arena);
+        let tournament = match settings.level {
+            Some(level) => tournament.with_level(level),
+            None => tournament,
+        };
         tournament.start()
     }
 }
@@ -1,9 +1,43 @@
+use pokefight_core::{
+    CacheConfig, CachedSource, OfflineSource, PokeService, PokemonSource, Result, TypeChart,
+};
+use std::path::Path;
 use std::sync::Arc;
-use pokefight_core::PokeService;
 
 use crate::adapters::http::app_state::AppState;
 
-pub async fn init_app_state() -> AppState {
-    let poke_service = Arc::new(PokeService::default());
-    AppState { poke_service }
+/// Set to `1` or `true` to serve Pokemon from the bundled Pokedex.
+const OFFLINE_ENV: &str = "POKEFIGHT_OFFLINE";
+/// Path to a custom `.json` or `.toml` type chart.
+const TYPE_CHART_ENV: &str = "POKEFIGHT_TYPE_CHART";
+
+/// Fails when the custom type chart can't be loaded.
+pub async fn init_app_state() -> Result<AppState> {
+    let offline = std::env::var(OFFLINE_ENV)
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+
+    let poke_service: Arc<dyn PokemonSource> = if offline {
+        let source = OfflineSource::new();
+        println!("Using bundled offline Pokedex (#1-{})", source.max_id());
+        Arc::new(source)
+    } else {
+        Arc::new(CachedSource::new(
+            PokeService::default(),
+            CacheConfig::default(),
+        ))
+    };
+
+    let type_chart = match std::env::var(TYPE_CHART_ENV) {
+        Ok(path) => {
+            println!("Using type chart from {}", path);
+            TypeChart::from_file(Path::new(&path))?
+        }
+        Err(_) => TypeChart::standard(),
+    };
+
+    Ok(AppState {
+        poke_service,
+        type_chart: Arc::new(type_chart),
+    })
 }
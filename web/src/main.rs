@@ -1,16 +1,17 @@
 use adapters::http::router::init_router;
 use infra::app_state::init_app_state;
+use pokefight_core::Result;
 
 mod adapters;
 mod application;
 mod infra;
 
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<()> {
     println!("PokeFight Web Service starting...");
 
     // Initialize AppState
-    let app_state = init_app_state().await;
+    let app_state = init_app_state().await?;
 
     // Initialize router with state
     let router = init_router().with_state(app_state);
@@ -21,4 +22,5 @@ async fn main() {
 
     println!("Server listening on http://127.0.0.1:3001");
     axum::serve(listener, router).await.unwrap();
+    Ok(())
 }